@@ -27,6 +27,84 @@ pub enum GameError {
     WeOnlyAllowStartingTheGameWithAtLeastOnePlayer,
     InkEnvError(String),
     ValueWasNotSetWhenStartingTheGame,
+    OnlyAdminCanConfigureTheGame,
+    GameCanOnlyBeConfiguredInTheFormingPhase,
+    PlayerFailedConformanceCheck,
+    CodeHashIsNotAllowed,
+    OnlyHumanPlayersCanQueueMoves,
+    MoveQueueIsFull,
+    GameOnlyAcceptsSignedTurns,
+    GameDoesNotAcceptSignedTurns,
+    OnlyRelayerCanSubmitSignedTurns,
+    PlayerIsNotRegistered,
+    OnlyOwnerCanResignPlayer,
+    PlayerHasAlreadyLeftTheGame,
+    PlayersCanOnlyResignWhileTheGameIsOn,
+    OnlyRefereeCanPenalizePlayers,
+    PlayersCanOnlyBePenalizedBeforeTheGameIsFinished,
+    InvalidLengthForReason,
+    OnlyAdminCanStartARematch,
+    RematchCanOnlyBeConfirmedInTheFormingPhase,
+    OnlyOwnerCanConfirmRematch,
+    RematchIsAlreadyConfirmed,
+    InvalidShare,
+    OnlyOwnerCanChangeAutoReentry,
+    OnlyAdminCanEndTheSeason,
+    NoSeasonToEnd,
+    OnlyTreasuryCanSweep,
+    NothingToSweep,
+    NoPrizeToClaim,
+    PotCanOnlyBeSponsoredBeforeTheGameEnded,
+    SponsorshipNeedsValue,
+    MaximumSponsorCountReached,
+    InvalidLengthForMessage,
+    TokenTransferFailed(PSP22Error),
+    TokenCallFailed,
+    NativeValueIsNotAccepted,
+    CurrencyCanOnlyBeChangedWhileNothingIsOwed,
+    TooManyTrophies,
+    RegistryIsNotConfigured,
+    NoIdentityInRegistry,
+    NameIsReservedByAnotherAccount,
+    RegistryCallFailed,
+    NotAllowedWhileARoundIsPlayed,
+    AllPrizesNeedToBeClaimedFirst,
+    HistoryDepthIsTooLarge,
+    StrikeLimitMustBeAtLeastOne,
+    OnlyAdminCanResetTheGame,
+    KeeperFundCanOnlyBeToppedUpBeforeTheGameEnded,
+}
+
+/// The error type of the PSP22 token standard.
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22Error {
+    Custom(String),
+    InsufficientBalance,
+    InsufficientAllowance,
+    ZeroRecipientAddress,
+    ZeroSenderAddress,
+    SafeTransferCheckFailed(String),
+}
+
+/// The error type of the PSP34 token standard.
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP34Error {
+    Custom(String),
+    SelfApprove,
+    NotApproved,
+    TokenExists,
+    TokenNotExists,
+    SafeTransferCheckFailed(String),
+}
+
+/// The error type of the ratings contract.
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum RatingsError {
+    OnlyAdminCanConfigure,
+    GameCodeHashIsNotAllowed,
 }
 
 impl From<Error> for GameError {
@@ -16,5 +16,48 @@ pub const PLAYER_LIMIT: usize = 80;
 /// for the overhead of the game contract itself.
 pub const GAS_LIMIT_ALL_PLAYERS: u64 = 250_000_000_000;
 
+/// The amount of gas a player is charged for a move that wasn't computed on-chain.
+///
+/// This applies to moves taken from the queue of a human player and to moves relayed
+/// through `submit_signed_turns`.
+pub const GAS_PER_QUEUED_MOVE: u64 = 2_000_000_000;
+
+/// The amount of moves a human player can queue up in advance.
+pub const MOVE_QUEUE_LIMIT: usize = 16;
+
 /// Maximum number of bytes in a players name.
 pub const ALLOWED_NAME_SIZES: RangeInclusive<usize> = 3..=16;
+
+/// The amount of sponsorships a single game accepts.
+pub const SPONSOR_LIMIT: usize = 32;
+
+/// Number of bytes allowed in the message of a sponsorship.
+pub const ALLOWED_MESSAGE_SIZES: RangeInclusive<usize> = 0..=64;
+
+/// The amount of top players that can be awarded a trophy.
+pub const TROPHY_LIMIT: u8 = 10;
+
+/// The amount of players that can collect season points in a single season.
+pub const SEASON_LIMIT: u32 = 256;
+
+/// The amount of gas the trophy collection can use to mint a single trophy.
+///
+/// Limited so that a collection that burns gas can't keep a game from ending.
+pub const TROPHY_MINT_GAS_LIMIT: u64 = 5_000_000_000;
+
+/// The amount of gas the ratings contract can use to rate the owners of a game.
+///
+/// Limited so that a ratings contract that burns gas can't keep a game from ending.
+pub const RATINGS_REPORT_GAS_LIMIT: u64 = 50_000_000_000;
+
+/// Shares of the pot are given in basis points of this.
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+/// Number of bytes allowed in the team of an identity.
+pub const ALLOWED_TEAM_SIZES: RangeInclusive<usize> = 1..=32;
+
+/// The maximum number of rounds for which snapshots are kept.
+pub const HISTORY_DEPTH_LIMIT: u32 = 64;
+
+/// Number of bytes allowed in the reason the referee gives for a penalty.
+pub const ALLOWED_REASON_SIZES: RangeInclusive<usize> = 1..=128;
@@ -1,8 +1,19 @@
 use core::cmp::Reverse;
 
+use ink::env::{
+    DefaultEnvironment,
+    Environment,
+};
 use ink::prelude::string::String;
 use ink::prelude::vec::Vec;
-use ink::primitives::AccountId;
+use ink::primitives::{
+    AccountId,
+    Hash,
+};
+use ink::LangError;
+
+/// The balance type of the chain the game runs on.
+pub type Balance = <DefaultEnvironment as Environment>::Balance;
 
 #[derive(scale::Decode, scale::Encode)]
 #[cfg_attr(
@@ -39,7 +50,16 @@ pub enum State {
         /// The number of rounds that are already played in the current game.
         rounds_played: u32,
     },
-    /// The game is finished an the pot has been payed out to the `winner`.
+    /// The game was concluded early because playing on is pointless.
+    ///
+    /// No turns can be submitted anymore and anybody can call `end_game`.
+    Ended {
+        /// The number of rounds that were played.
+        rounds_played: u32,
+        /// Why the game was concluded early.
+        reason: ConclusionReason,
+    },
+    /// The game is finished an the pot has been awarded to the `winner`.
     Finished {
         /// The player with the highest score when the game ended.
         ///
@@ -58,17 +78,353 @@ pub enum State {
 pub struct Player {
     pub id: AccountId,
     pub name: String,
+    /// The account that registered the player.
+    pub owner: AccountId,
+    /// Whether the player is a contract or makes its moves by hand.
+    pub kind: PlayerKind,
+    /// Whether the player still takes part in the game.
+    pub status: PlayerStatus,
+    /// The number of turns in a row that were broken or out of bounds.
+    pub strikes: u32,
+    /// The player sits out all rounds before this one.
+    pub frozen_until: u32,
+    /// Statistics about the turns the player made.
+    pub stats: TurnStats,
+    /// The amount of gas this player was charged for.
     pub gas_used: u64,
     pub score: u64,
+    /// Points collected over all games of a series of rematches.
+    pub series_points: u32,
 }
 
 impl Player {
+    /// A new player that didn't make any turns yet.
+    pub fn new(id: AccountId, name: String, owner: AccountId, kind: PlayerKind) -> Self {
+        Self {
+            id,
+            name,
+            owner,
+            kind,
+            status: PlayerStatus::Active,
+            strikes: 0,
+            frozen_until: 0,
+            stats: Default::default(),
+            gas_used: 0,
+            score: 0,
+            series_points: 0,
+        }
+    }
+
+    /// Reset everything but the series points for a rematch.
+    ///
+    /// The player has to confirm the rematch before it can play again.
+    pub fn prepare_rematch(&mut self) {
+        *self = Self {
+            status: PlayerStatus::Unconfirmed,
+            series_points: self.series_points,
+            ..Self::new(self.id, core::mem::take(&mut self.name), self.owner, self.kind)
+        };
+    }
+
     /// Return the key to sort by (winner is min value by this order)
+    ///
+    /// Players that left the game rank below all active players and disqualified
+    /// players rank last.
     pub fn scoring_order(&self) -> impl Ord {
-        (Reverse(self.score), self.gas_used)
+        (self.status, Reverse(self.score), self.gas_used)
+    }
+
+    /// Whether the player still makes turns.
+    pub fn is_active(&self) -> bool {
+        self.status == PlayerStatus::Active
+    }
+}
+
+/// Statistics about the turns of a player.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct TurnStats {
+    /// Turns that painted a field.
+    pub successes: u32,
+    /// Turns that tried to paint an already painted field.
+    pub occupied: u32,
+    /// Turns that were outside of the board.
+    pub out_of_bounds: u32,
+    /// Turns where the player failed to return a result.
+    pub broken: u32,
+    /// Turns where the player decided not to paint.
+    pub no_turn: u32,
+    /// The number of fields the player currently owns.
+    pub cells_owned: u32,
+    /// The gas the player was charged for its most recently recorded turn.
+    ///
+    /// Only the latest turn is kept: the total is in [`Player::gas_used`]. Turns in
+    /// which the player decided not to paint are free.
+    pub last_turn_gas_charged: u64,
+}
+
+impl TurnStats {
+    /// Account for a turn with the given outcome.
+    pub fn record(&mut self, outcome: &TurnOutcome, gas_charged: u64) {
+        let counter = match outcome {
+            TurnOutcome::Success { .. } => {
+                self.cells_owned = self.cells_owned.saturating_add(1);
+                &mut self.successes
+            }
+            TurnOutcome::Occupied { .. } => &mut self.occupied,
+            TurnOutcome::OutOfBounds { .. } => &mut self.out_of_bounds,
+            TurnOutcome::BrokenPlayer { .. } => &mut self.broken,
+            TurnOutcome::NoTurn => &mut self.no_turn,
+            TurnOutcome::BudgetExhausted
+            | TurnOutcome::Frozen
+            | TurnOutcome::Cranked => return,
+        };
+        *counter = counter.saturating_add(1);
+        self.last_turn_gas_charged = gas_charged;
     }
 }
 
+/// Score and gas of all players after a round.
+#[derive(scale::Decode, scale::Encode, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct RoundSnapshot {
+    /// The round after which the snapshot was taken.
+    pub round: u32,
+    /// The state of every player after `round`.
+    pub players: Vec<PlayerSnapshot>,
+}
+
+/// Score and gas of a single player after a round.
+#[derive(scale::Decode, scale::Encode, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct PlayerSnapshot {
+    /// The player.
+    pub id: AccountId,
+    /// The score of the player.
+    pub score: u64,
+    /// The gas used by the player so far.
+    pub gas_used: u64,
+}
+
+/// The final standings of a game.
+#[derive(scale::Decode, scale::Encode, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct GameResult {
+    /// All players ordered by their rank.
+    pub standings: Vec<Standing>,
+    /// The number of rounds that were played.
+    pub rounds_played: u32,
+    /// The hash of the final board.
+    pub board_hash: Hash,
+}
+
+/// The final standing of a single player.
+#[derive(scale::Decode, scale::Encode, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Standing {
+    /// The player.
+    pub id: AccountId,
+    /// The account that registered the player.
+    pub owner: AccountId,
+    /// The name of the player.
+    pub name: String,
+    /// Whether the player was still active when the game ended.
+    pub status: PlayerStatus,
+    /// The final score of the player.
+    pub score: u64,
+    /// The gas the player was charged for.
+    pub gas_used: u64,
+    /// The amount the owner of the player was awarded.
+    pub payout: Balance,
+}
+
+/// The different categories of players.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum PlayerKind {
+    /// A contract that is called every round to make its turn.
+    Contract,
+    /// A plain account that queues its moves by calling `queue_move`.
+    Human,
+}
+
+/// Whether a player still takes part in the game.
+///
+/// The order of the variants is used for ranking.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum PlayerStatus {
+    /// The player makes turns.
+    Active,
+    /// The player resigned and isn't called anymore.
+    Resigned,
+    /// The player broke too many turns in a row. It isn't called anymore and can't
+    /// win the pot.
+    Disqualified,
+    /// The player played the previous game but didn't confirm the rematch yet.
+    ///
+    /// Unconfirmed players are removed when the game starts.
+    Unconfirmed,
+}
+
+/// A penalty the referee gave to a player.
+#[derive(scale::Decode, scale::Encode, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Penalty {
+    /// The player that was penalized.
+    pub player: AccountId,
+    /// What was done to the player.
+    pub action: PenaltyAction,
+    /// Why the player was penalized.
+    pub reason: String,
+    /// The number of rounds played when the penalty was given.
+    pub round: u32,
+}
+
+/// Value someone added to the pot by calling `sponsor`.
+#[derive(scale::Decode, scale::Encode, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Sponsorship {
+    /// The account that sponsored the pot.
+    pub sponsor: AccountId,
+    /// The amount added to the pot.
+    pub amount: Balance,
+    /// A message shown alongside the sponsorship.
+    pub message: String,
+}
+
+/// A display name and metadata an owner reserved in the identity registry.
+#[derive(scale::Decode, scale::Encode, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Identity {
+    /// The display name that is unique across all games using the registry.
+    pub name: String,
+    /// The hash of an avatar image.
+    pub avatar: Option<Hash>,
+    /// The team the owner plays for.
+    pub team: Option<String>,
+}
+
+/// The different penalties the referee can give.
+#[derive(scale::Decode, scale::Encode, Clone, Copy)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum PenaltyAction {
+    /// Points were taken away from the player.
+    DeductScore {
+        /// The number of points that were taken away.
+        amount: u64,
+    },
+    /// The player sits out the next rounds.
+    Freeze {
+        /// The number of rounds the player sits out.
+        rounds: u32,
+    },
+    /// The fields of the player inside a region were released back to the board.
+    StripFields {
+        /// The top left corner of the region.
+        from: Field,
+        /// The bottom right corner of the region (inclusive).
+        to: Field,
+        /// The number of fields that were released.
+        released: u32,
+    },
+}
+
+/// Where the moves of the players come from.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum TurnMode {
+    /// Rounds are played by calling `submit_turn` which calls every player contract
+    /// and takes the queued moves of human players.
+    Calls,
+    /// Rounds are played by relaying moves signed off-chain by the owners of the
+    /// players through `submit_signed_turns`.
+    SignedMoves,
+}
+
+/// A move signed off-chain by the owner of a player.
+#[derive(scale::Decode, scale::Encode, Clone, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct SignedMove {
+    /// The player that makes the move.
+    pub player: AccountId,
+    /// The field the player wants to paint.
+    pub turn: Field,
+    /// ECDSA signature of the owner over the blake2-256 hash of the SCALE encoded
+    /// `(game, games_started, round, turn)`.
+    ///
+    /// `games_started` is the number of games the contract started including the
+    /// current one so that moves can't be replayed in a later game.
+    pub signature: [u8; 65],
+}
+
+/// Why a game was concluded before all rounds were played.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum ConclusionReason {
+    /// Every field of the board is painted.
+    BoardFull,
+    /// None of the active players has any budget left.
+    AllBudgetsExhausted,
+    /// All players left the game.
+    NoActivePlayers,
+}
+
+/// How player contracts are vetted when they are registered.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum RegistrationCheck {
+    /// Any account can be registered.
+    Disabled,
+    /// The candidate is called with a sample [`GameInfo`] in a reverted sub-call and
+    /// needs to return an `Option<Field>` within the gas limit.
+    DryCall,
+    /// Only contracts whose code hash was allowed by the opener can be registered.
+    CodeHashAllowlist,
+}
+
 /// Describing either a single point in the field or its dimensions.
 #[derive(scale::Decode, scale::Encode, Clone, Copy, Debug)]
 #[cfg_attr(
@@ -86,6 +442,10 @@ impl Field {
     pub fn len(&self) -> u32 {
         self.x.saturating_mul(self.y)
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 /// Info for each occupied board entry.
@@ -126,11 +486,187 @@ pub enum TurnOutcome {
         /// The player that occupies the field that was tried to be painted by `turn`.
         player: AccountId,
     },
-    /// Player contract failed to return a result. This happens if it
-    /// panicked, ran out of gas, returns garbage or is not even a contract.
-    BrokenPlayer,
+    /// Player contract failed to return a result.
+    BrokenPlayer {
+        /// Why the player failed to return a result.
+        reason: TurnFailure,
+    },
     /// Player decided to not make a turn and hence was charged no gas.
     NoTurn,
     /// Contract doesn't have any budget left and isn't called anymore.
     BudgetExhausted,
+    /// The referee froze the player and it sits this round out.
+    Frozen,
+    /// The player contract advanced the round itself. It can't be called back so it
+    /// sits this round out without a penalty.
+    Cranked,
+}
+
+impl TurnOutcome {
+    /// The kind of the outcome without any of its data.
+    pub fn kind(&self) -> OutcomeKind {
+        match self {
+            Self::Success { .. } => OutcomeKind::Success,
+            Self::OutOfBounds { .. } => OutcomeKind::OutOfBounds,
+            Self::Occupied { .. } => OutcomeKind::Occupied,
+            Self::BrokenPlayer { .. } => OutcomeKind::BrokenPlayer,
+            Self::NoTurn => OutcomeKind::NoTurn,
+            Self::BudgetExhausted => OutcomeKind::BudgetExhausted,
+            Self::Frozen => OutcomeKind::Frozen,
+            Self::Cranked => OutcomeKind::Cranked,
+        }
+    }
+
+    /// The field the player returned if it made a turn.
+    pub fn turn(&self) -> Option<Field> {
+        match self {
+            Self::Success { turn }
+            | Self::OutOfBounds { turn }
+            | Self::Occupied { turn, .. } => Some(*turn),
+            _ => None,
+        }
+    }
+}
+
+/// The kinds of [`TurnOutcome`] without their data.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum OutcomeKind {
+    Success,
+    OutOfBounds,
+    Occupied,
+    BrokenPlayer,
+    NoTurn,
+    BudgetExhausted,
+    Frozen,
+    Cranked,
+}
+
+/// A compact version of a player's turn used in round summaries.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct TurnSummary {
+    /// The index of the player in the list of players ordered by their ID.
+    pub player: u8,
+    /// What happened.
+    pub outcome: OutcomeKind,
+    /// The field the player returned if it made a turn.
+    pub turn: Option<Field>,
+}
+
+/// Configuration of a game that keeps reopening after it ended.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct ArenaConfig {
+    /// The number of blocks between the end of a game and the start of the next.
+    pub cooldown: u32,
+    /// The share of each pot in basis points that goes into the season pot.
+    pub season_share_bps: u16,
+}
+
+/// Configuration of the fee paid to whoever advances a round.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct KeeperConfig {
+    /// The amount paid for every round.
+    pub fee: Balance,
+    /// The share of the pot in basis points moved to the keeper fund when the game
+    /// starts.
+    pub pot_share_bps: u16,
+    /// The maximum number of rounds a single keeper is paid for per game.
+    pub max_rounds_per_keeper: u32,
+}
+
+/// Configuration of the trophies minted when a game ends.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct TrophyConfig {
+    /// The PSP34 collection the trophies are minted in, like the `trophies`
+    /// contract.
+    pub collection: AccountId,
+    /// The number of top players that get a trophy.
+    pub count: u8,
+}
+
+/// The metadata of a trophy passed to `Trophies::mint` of the collection.
+#[derive(scale::Decode, scale::Encode, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Trophy {
+    /// The game the trophy was won in.
+    pub game: AccountId,
+    /// The final rank of the player starting at 1.
+    pub rank: u32,
+    /// The final score of the player.
+    pub score: u64,
+    /// The gas the player was charged for.
+    pub gas_used: u64,
+    /// The hash of the final board.
+    pub board_hash: Hash,
+}
+
+/// Which events are emitted while the game is played.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "std",
+    derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum EventMode {
+    /// A `TurnTaken` event for every turn and a `RoundIncremented` event per round.
+    PerTurn,
+    /// A single `RoundSummary` event per round.
+    RoundSummary,
+}
+
+/// What would happen if a player made its turn right now.
+#[derive(scale::Decode, scale::Encode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct TurnPreview {
+    /// The field returned by the player, if any.
+    pub turn: Option<Field>,
+    /// The gas consumed by the player's call.
+    pub gas_used: u64,
+    /// The outcome the turn would have produced.
+    pub outcome: TurnOutcome,
+}
+
+/// The reasons why a player contract can fail to make a turn.
+#[derive(scale::Decode, scale::Encode, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum TurnFailure {
+    /// The contract panicked or otherwise trapped.
+    Trapped,
+    /// The contract consumed all the gas it was given for this turn.
+    OutOfGas,
+    /// The contract could not decode the [`GameInfo`] passed to it or returned
+    /// something that isn't an `Option<Field>`.
+    DecodingFailed {
+        /// The error reported by the contract in case it couldn't decode its input.
+        ///
+        /// `None` if the contract returned a value we couldn't decode.
+        error: Option<LangError>,
+    },
+    /// The account isn't a contract.
+    NotAContract,
+}
+
+// `LangError` doesn't implement `StorageLayout` so we can't derive it.
+#[cfg(feature = "std")]
+impl ink::storage::traits::StorageLayout for TurnFailure {
+    fn layout(key: &ink::primitives::Key) -> ink::metadata::layout::Layout {
+        ink::metadata::layout::Layout::Leaf(ink::metadata::layout::LeafLayout::from_key::<
+            Self,
+        >(key.into()))
+    }
 }
@@ -37,6 +37,7 @@ export const Board: React.FC<Props> = ({ className, board, dimensions, boardWidt
     ? {
         Forming: '#ffc32b',
         Running: '#50d985',
+        Ended: '#ff705e',
         Finished: '#ff705e',
       }[status]
     : '#ffc32b';
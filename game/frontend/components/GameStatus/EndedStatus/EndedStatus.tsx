@@ -0,0 +1,24 @@
+import { useTranslation } from 'react-i18next';
+import { Ended } from '../../../hooks/useGameContract';
+
+type Props = {
+  ended: Ended;
+};
+
+export const EndedStatus: React.FC<Props> = ({ ended }) => {
+  const categoryClass = 'mr-1';
+  const { t } = useTranslation('common');
+
+  return (
+    <>
+      <h6>
+        <span className={categoryClass}>{t('status')}:</span>
+        <span className="font-normal bg-players-3 text-white rounded-full px-2 py-[2px]">{t('complete')}</span>
+      </h6>
+      <h6 className="my-2">
+        <span className={categoryClass}>{t('block')}:</span>
+        <span className="font-normal bg-brand-500 text-white rounded-full px-2 py-[2px]">{ended.roundsPlayed}</span>
+      </h6>
+    </>
+  );
+};
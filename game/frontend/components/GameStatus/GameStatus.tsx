@@ -1,6 +1,7 @@
 import { GiBackup } from 'react-icons/gi';
 import { useGameState, usePlayers } from '../../hooks/useGameContract';
 import { SimpleWidget } from '../SimpleWidget';
+import { EndedStatus } from './EndedStatus';
 import { FinishedStatus } from './FinishedStatus';
 import { FormingStatus } from './FormingStatus';
 import { RunningStatus } from './RunningStatus';
@@ -25,6 +26,7 @@ export const GameStatus: React.FC = () => {
     <SimpleWidget>
       {'Forming' === gameState?.status && <FormingStatus forming={gameState} />}
       {'Running' === gameState?.status && <RunningStatus running={gameState} />}
+      {'Ended' === gameState?.status && <EndedStatus ended={gameState} />}
       {'Finished' === gameState?.status && <FinishedStatus finished={gameState} />}
 
       <span className="flex items-center justify-between mt-2">
@@ -64,7 +64,9 @@ const useGameValues = (): Game => {
 
         if (!results[coordinates]) results[coordinates] = [];
 
-        const outcomeType = Object.keys(event.args[1] as {})[0];
+        // Outcomes without data are decoded as plain strings.
+        const outcome = event.args[1] as any;
+        const outcomeType = typeof outcome === 'string' ? outcome : Object.keys(outcome || {})[0];
         switch (outcomeType) {
           case 'Success':
             let successEvent: TurnEvent = { ...base, name: 'Success', turn };
@@ -85,7 +87,7 @@ const useGameValues = (): Game => {
             break;
 
           case 'BrokenPlayer':
-            const brokenPlayerEvent: TurnEvent = { ...base, name: 'BrokenPlayer' };
+            const brokenPlayerEvent: TurnEvent = { ...base, name: 'BrokenPlayer', reason: outcome.BrokenPlayer.reason };
             playerTurns.push(brokenPlayerEvent);
             results[coordinates].push();
             break;
@@ -19,20 +19,49 @@ export type Running = {
   currentRound: number;
 };
 
+export type ConclusionReason = 'BoardFull' | 'AllBudgetsExhausted' | 'NoActivePlayers';
+
+export type Ended = {
+  status: 'Ended';
+  roundsPlayed: number;
+  reason: ConclusionReason;
+};
+
 export type Finished = {
   status: 'Finished';
   winner: AccountId;
 };
 
-export type GameStatus = 'Forming' | 'Running' | 'Finished';
+export type GameStatus = 'Forming' | 'Running' | 'Ended' | 'Finished';
+
+export type GameState = Forming | Running | Ended | Finished;
 
-export type GameState = Forming | Running | Finished;
+export type PlayerKind = 'Contract' | 'Human';
+
+export type PlayerStatus = 'Active' | 'Resigned' | 'Disqualified' | 'Unconfirmed';
+
+export type TurnStats = {
+  successes: number;
+  occupied: number;
+  outOfBounds: number;
+  broken: number;
+  noTurn: number;
+  cellsOwned: number;
+  lastTurnGasCharged: string;
+};
 
 export type Player = {
   id: AccountId;
   name: string;
+  owner: AccountId;
+  kind: PlayerKind;
+  status: PlayerStatus;
+  strikes: number;
+  frozenUntil: number;
+  stats: TurnStats;
   gasUsed: string;
   score: number;
+  seriesPoints: number;
 };
 
 export type PlayerList = { [accountId: string]: string };
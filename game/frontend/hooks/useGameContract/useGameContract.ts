@@ -6,6 +6,7 @@ import { useContractCallDecoded } from '../../lib/useInk/hooks/useContractCallDe
 import {
   BoardPosition,
   Dimensions,
+  Ended,
   Field,
   Forming,
   GameState,
@@ -43,6 +44,15 @@ const toRunningStatus = (gameState: any, totalRounds: number): Running => {
   };
 };
 
+const toEndedStatus = (gameState: any): Ended => {
+  const roundsPlayed = stringNumberToBN(gameState.Ended.roundsPlayed).toNumber() || 0;
+  return {
+    status: 'Ended',
+    roundsPlayed,
+    reason: gameState.Ended.reason,
+  };
+};
+
 const toFormingStatus = (gameState: any, currentBlock: number): Forming => {
   const earliestStart = stringNumberToBN(gameState.Forming?.earliestStart).toNumber() || 0;
   const startingIn = currentBlock < earliestStart ? earliestStart - currentBlock : 0;
@@ -76,6 +86,10 @@ export const useGameState = (): GameState | null => {
         setGameState(toRunningStatus(result.value.result, totalRounds.value.result));
         break;
 
+      case 'ended':
+        setGameState(toEndedStatus(result.value.result));
+        break;
+
       case 'finished':
         setGameState({
           status: 'Finished',
@@ -33,9 +33,16 @@ export type Occupied = {
   turn: Field;
 };
 
+export type TurnFailure =
+  | 'Trapped'
+  | 'OutOfGas'
+  | { DecodingFailed: { error: string | null } }
+  | 'NotAContract';
+
 export type BrokenPlayer = {
   name: 'BrokenPlayer';
   player: string;
+  reason: TurnFailure;
 };
 
 export type Turn = SuccessfulTurn | OutOfBoundsTurn | Occupied | BrokenPlayer;
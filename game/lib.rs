@@ -7,6 +7,7 @@ pub use contract::{
 
 #[ink::contract]
 mod contract {
+    use core::cmp::Reverse;
     use ink::{
         env::{
             call::{
@@ -16,13 +17,17 @@ mod contract {
                 Selector,
             },
             debug_println,
+            hash::Blake2x256,
             CallFlags,
             DefaultEnvironment,
+            Error as EnvError,
+            ReturnFlags,
         },
         prelude::{
             string::String,
             vec::Vec,
         },
+        primitives::MessageResult,
         storage::{
             Lazy,
             Mapping,
@@ -48,6 +53,104 @@ mod contract {
         last_turn: Lazy<u32>,
         /// The opener is allowed to start the game early.
         opener: AccountId,
+        /// The reason of the last failed turn of each player.
+        last_errors: Mapping<AccountId, TurnFailure>,
+        /// How player contracts are vetted when they are registered.
+        registration_check: RegistrationCheck,
+        /// Code hashes of player contracts that can be registered.
+        allowed_code_hashes: Mapping<Hash, ()>,
+        /// Moves queued by human players that are yet to be played.
+        move_queues: Mapping<AccountId, Vec<Field>>,
+        /// Where the moves of the players come from.
+        turn_mode: TurnMode,
+        /// The account that is allowed to relay signed moves.
+        relayer: AccountId,
+        /// The number of fields that are already painted.
+        claimed_cells: u32,
+        /// Whether the fields of a player are released when it resigns.
+        release_fields_on_resign: bool,
+        /// After how many broken or out of bounds turns in a row a player is
+        /// disqualified.
+        strike_limit: Option<u32>,
+        /// Whether the fields of a player are released when it is disqualified.
+        release_fields_on_disqualification: bool,
+        /// The account that is allowed to penalize players.
+        referee: AccountId,
+        /// All penalties the referee gave.
+        audit_log: Mapping<u32, Penalty>,
+        /// The number of entries in `audit_log`.
+        audit_log_len: u32,
+        /// Ring buffer of the most recent round snapshots.
+        history: Mapping<u32, RoundSnapshot>,
+        /// The number of rounds for which snapshots are kept.
+        history_depth: u32,
+        /// The final standings of the last game that finished.
+        ///
+        /// Kept outside of the state so that it is only loaded when needed.
+        last_result: Lazy<GameResult>,
+        /// Rolling hash over the fields painted in every round.
+        commitments: Mapping<u32, Hash>,
+        /// Which events are emitted while the game is played.
+        event_mode: EventMode,
+        /// The number of games played since the roster was last cleared.
+        series_games: u32,
+        /// Reopen the game after it ended if set.
+        arena: Option<ArenaConfig>,
+        /// Players that stay registered when the arena reopens.
+        ///
+        /// The buy-ins for the next games are taken from the credit.
+        reentry_credit: Mapping<AccountId, Balance>,
+        /// The sum of all reentry credits.
+        reentry_total: Balance,
+        /// The owner and the season points of every player that finished an arena
+        /// game.
+        season_points: Mapping<AccountId, (AccountId, u32)>,
+        /// The players that have season points by the order they got their first.
+        season_players: Mapping<u32, AccountId>,
+        /// The number of players that have season points.
+        ///
+        /// Bounded by [`SEASON_LIMIT`]. Players that come later get no season points.
+        season_len: u32,
+        /// The part of the pots that is paid out when the season ends.
+        season_pot: Balance,
+        /// The buy-ins of the current game that will be paid out to the winner.
+        pot: Balance,
+        /// Pay whoever advances a round if set.
+        keeper: Option<KeeperConfig>,
+        /// The balance keeper fees are paid from.
+        keeper_fund: Balance,
+        /// The number of games that were started.
+        games_started: u32,
+        /// How many rounds every keeper was paid for in the game they were paid last.
+        keeper_rounds: Mapping<AccountId, (u32, u32)>,
+        /// The share of the pot in basis points that goes to the treasury.
+        rake_bps: u16,
+        /// The account that collects the rake and can [`sweep`] leftover balance.
+        treasury: AccountId,
+        /// Amounts owed to accounts that they can collect with [`claim_prize`].
+        prizes: Mapping<AccountId, Balance>,
+        /// The sum of all unclaimed prizes.
+        prizes_total: Balance,
+        /// Everyone who added to the pot of the current game.
+        sponsors: Lazy<Vec<Sponsorship>>,
+        /// The share of the pot in basis points that carries over into the next game.
+        rollover_bps: u16,
+        /// The part of the last pot that is added to the pot of the next game.
+        rollover: Balance,
+        /// The PSP22 token buy-ins and prizes are paid in or `None` for the native
+        /// currency.
+        buy_in_token: Option<AccountId>,
+        /// Mint trophies to the top players when the game ends if set.
+        trophies: Option<TrophyConfig>,
+        /// The ratings contract the result is reported to when the game ends if set.
+        ratings: Option<AccountId>,
+        /// Whether players are being called right now.
+        ///
+        /// This is written to storage right away so that players calling back into
+        /// the game see it.
+        in_round: Lazy<bool>,
+        /// The identity registry names are checked against if set.
+        registry: Option<AccountId>,
     }
 
     /// A player joined the game by calling [`register_player`].
@@ -78,11 +181,158 @@ mod contract {
     #[ink(event)]
     pub struct TurnTaken {
         /// The player that attempted the turn.
+        #[ink(topic)]
         player: AccountId,
         /// The effect of the turn that was performed by the player.
         outcome: TurnOutcome,
     }
 
+    /// All turns of a round.
+    ///
+    /// Emitted instead of [`TurnTaken`] and [`RoundIncremented`] when the game is
+    /// configured with [`EventMode::RoundSummary`].
+    #[ink(event)]
+    pub struct RoundSummary {
+        /// The number of rounds played.
+        rounds_played: u32,
+        /// What every player that was up in this round did.
+        turns: Vec<TurnSummary>,
+    }
+
+    /// A player left the game by calling [`resign`].
+    #[ink(event)]
+    pub struct PlayerResigned {
+        /// The player that resigned.
+        player: AccountId,
+        /// Whether the fields of the player were released back to the board.
+        fields_released: bool,
+    }
+
+    /// A player broke too many turns in a row and was disqualified.
+    #[ink(event)]
+    pub struct PlayerDisqualified {
+        /// The player that was disqualified.
+        #[ink(topic)]
+        player: AccountId,
+        /// Whether the fields of the player were released back to the board.
+        fields_released: bool,
+    }
+
+    /// The referee penalized a player.
+    #[ink(event)]
+    pub struct PlayerPenalized {
+        /// The player that was penalized.
+        player: AccountId,
+        /// What was done to the player.
+        action: PenaltyAction,
+        /// Why the player was penalized.
+        reason: String,
+    }
+
+    /// The game was concluded before all rounds were played.
+    ///
+    /// [`end_game`] can be called right away to pay out the winner.
+    #[ink(event)]
+    pub struct GameConcluded {
+        /// Why the game was concluded early.
+        reason: ConclusionReason,
+    }
+
+    /// The opener started a rematch with the same players by calling [`rematch`].
+    #[ink(event)]
+    pub struct RematchStarted {
+        /// The number of games already played in this series.
+        series_games: u32,
+    }
+
+    /// A player confirmed the rematch by calling [`confirm_rematch`].
+    #[ink(event)]
+    pub struct RematchConfirmed {
+        /// The player that confirmed.
+        #[ink(topic)]
+        player: AccountId,
+    }
+
+    /// The rake was taken from the pot and sent to the treasury.
+    #[ink(event)]
+    pub struct RakeCollected {
+        /// The account that received the rake.
+        treasury: AccountId,
+        /// The amount taken from the pot.
+        amount: Balance,
+    }
+
+    /// Someone added to the pot by calling [`sponsor`].
+    #[ink(event)]
+    pub struct Sponsored {
+        /// The account that sponsored the pot.
+        #[ink(topic)]
+        sponsor: AccountId,
+        /// The amount added to the pot.
+        amount: Balance,
+        /// A message shown alongside the sponsorship.
+        message: String,
+    }
+
+    /// An account collected what it was owed by calling [`claim_prize`].
+    #[ink(event)]
+    pub struct PrizeClaimed {
+        /// The account the prize was paid to.
+        #[ink(topic)]
+        account: AccountId,
+        /// The amount that was paid.
+        amount: Balance,
+    }
+
+    /// The treasury collected leftover balance by calling [`sweep`].
+    #[ink(event)]
+    pub struct Swept {
+        /// The account that received the balance.
+        treasury: AccountId,
+        /// The amount that was collected.
+        amount: Balance,
+    }
+
+    /// Someone was paid for advancing a round.
+    #[ink(event)]
+    pub struct KeeperPaid {
+        /// The account that called [`submit_turn`] or [`submit_signed_turns`].
+        #[ink(topic)]
+        keeper: AccountId,
+        /// The fee that was paid.
+        amount: Balance,
+    }
+
+    /// The arena game ended and is open for registration again.
+    #[ink(event)]
+    pub struct ArenaReopened {
+        /// The block from which on the next game can be started.
+        earliest_start: u32,
+        /// The number of players that stayed registered.
+        players: u32,
+    }
+
+    /// The opener ended the season by calling [`end_season`].
+    #[ink(event)]
+    pub struct SeasonEnded {
+        /// The player with the most season points.
+        winner: AccountId,
+        /// The season pot that was awarded to the owner of the winner.
+        payout: Balance,
+    }
+
+    /// A trophy was minted for a top player when the game ended.
+    #[ink(event)]
+    pub struct TrophyMinted {
+        /// The owner of the player that received the trophy.
+        #[ink(topic)]
+        owner: AccountId,
+        /// The player the trophy was won by.
+        player: AccountId,
+        /// The final rank of the player starting at 1.
+        rank: u32,
+    }
+
     /// Someone ended the game by calling [`end_game`].
     ///
     /// This event doesn't contain information about the winner because the contract still
@@ -98,7 +348,21 @@ mod contract {
     #[ink(event)]
     pub struct GameDestroyed {
         /// The winning player who is also the one who destroyed the contract.
-        winner: Player,
+        ///
+        /// `None` if every player was disqualified and the opener destroyed it.
+        winner: Option<Player>,
+        /// The final standings of the game which can't be read from the contract
+        /// anymore.
+        result: GameResult,
+    }
+
+    /// What happened during a round.
+    #[derive(Default)]
+    struct RoundLog {
+        /// The fields painted in this round.
+        applied: Vec<(AccountId, Field)>,
+        /// The turns for the [`RoundSummary`] event.
+        turns: Vec<TurnSummary>,
     }
 
     impl SquinkSplash {
@@ -130,39 +394,97 @@ mod contract {
                 rounds,
                 last_turn: Default::default(),
                 opener: Self::env().caller(),
+                last_errors: Default::default(),
+                registration_check: RegistrationCheck::Disabled,
+                allowed_code_hashes: Default::default(),
+                move_queues: Default::default(),
+                turn_mode: TurnMode::Calls,
+                relayer: Self::env().caller(),
+                claimed_cells: 0,
+                release_fields_on_resign: false,
+                strike_limit: None,
+                release_fields_on_disqualification: false,
+                referee: Self::env().caller(),
+                audit_log: Default::default(),
+                audit_log_len: 0,
+                history: Default::default(),
+                history_depth: 0,
+                last_result: Default::default(),
+                commitments: Default::default(),
+                event_mode: EventMode::PerTurn,
+                series_games: 0,
+                arena: None,
+                reentry_credit: Default::default(),
+                reentry_total: 0,
+                season_points: Default::default(),
+                season_players: Default::default(),
+                season_len: 0,
+                season_pot: 0,
+                pot: 0,
+                keeper: None,
+                keeper_fund: 0,
+                games_started: 0,
+                keeper_rounds: Default::default(),
+                rake_bps: 0,
+                treasury: Self::env().caller(),
+                prizes: Default::default(),
+                prizes_total: 0,
+                sponsors: Default::default(),
+                rollover_bps: 0,
+                rollover: 0,
+                buy_in_token: None,
+                trophies: None,
+                ratings: None,
+                in_round: Default::default(),
+                registry: None,
             };
             ret.players.set(&Vec::new());
             ret
         }
 
         /// When the game is in finished the contract can be deleted by the winner.
+        ///
+        /// Everything the game owes to anyone needs to be paid out first. See
+        /// [`claim_prize`], [`end_season`] and [`release_reserves`].
         #[ink(message)]
         pub fn destroy(&mut self) -> Result<(), GameError> {
-            if let State::Finished { winner } = self.state {
+            if let State::Finished { winner, .. } = self.state {
                 winner
                     .eq(&Self::env().caller())
                     .then_some(())
                     .ok_or(GameError::OnlyWinnerIsAllowedToDestroyTheContract)?;
-
-                let winner = {
-                    let players = self.players();
-                    let winning_idx = Self::find_player(&winner, &players)
-                        .map_err(|_| GameError::TheWinnerIsNotAPlayer)?;
-
-                    players.into_iter().nth(winning_idx).unwrap()
+                (self.reserved() == 0)
+                    .then_some(())
+                    .ok_or(GameError::AllPrizesNeedToBeClaimedFirst)?;
+
+                // The opener takes the place of the winner if every player was
+                // disqualified.
+                let mut players = self.players();
+                let player = match Self::find_player(&winner, &players) {
+                    Ok(idx) => Some(players.swap_remove(idx)),
+                    Err(_) if winner == self.opener => None,
+                    Err(_) => return Err(GameError::TheWinnerIsNotAPlayer),
                 };
-                let winner_id = winner.id;
-                Self::env().emit_event(GameDestroyed { winner });
-                Self::env().terminate_contract(winner_id);
+                let result = self
+                    .last_result
+                    .get()
+                    .ok_or(GameError::OnlyFinishedGamesCanBeDestroyed)?;
+                Self::env().emit_event(GameDestroyed {
+                    winner: player,
+                    result,
+                });
+                Self::env().terminate_contract(winner);
             } else {
-                return Err(GameError::OnlyFinishedGamesCanBeDestroyed);
+                Err(GameError::OnlyFinishedGamesCanBeDestroyed)
             }
         }
 
         /// Anyone can start the game when `earliest_start` is reached.
+        ///
+        /// Only the opener can start a game unless it is an arena.
         #[ink(message)]
         pub fn start_game(&mut self) -> Result<(), GameError> {
-            if Self::env().caller() != self.opener {
+            if self.arena.is_none() && Self::env().caller() != self.opener {
                 return Err(GameError::OnlyAdminCanStartTheGame);
             }
 
@@ -173,13 +495,23 @@ mod contract {
             } else {
                 return Err(GameError::GameAlreadyStarted);
             };
-            let players = self.players();
+
+            // Players that didn't confirm the rematch don't take part.
+            let mut players = self.players();
+            players.retain(|player| player.status != PlayerStatus::Unconfirmed);
+            self.players.set(&players);
 
             let res = !players.is_empty();
             res.then_some(())
                 .ok_or(GameError::YouNeedAtLeastOnePlayer)?;
 
             self.state = State::Running { rounds_played: 0 };
+            self.games_started = self.games_started.saturating_add(1);
+            if let Some(keeper) = self.keeper {
+                let share = Self::share_of(self.pot, keeper.pot_share_bps);
+                self.keeper_fund = self.keeper_fund.saturating_add(share);
+                self.pot = self.pot.saturating_sub(share);
+            }
 
             // We pretend that there was already a turn in this block so that no
             // turns can be submitted in the same block as when the game is started.
@@ -193,51 +525,253 @@ mod contract {
 
         /// When enough time has passed, no new turns can be submitted.
         /// Then anybody may call this function to end the game and
-        /// trigger the payout to the winner.
+        /// award the pot to the winner.
+        ///
+        /// Nothing is transferred here. The owner of the winner and the treasury
+        /// collect what they are owed with [`claim_prize`].
         #[ink(message)]
         pub fn end_game(&mut self) -> Result<(), GameError> {
-            let res = !self.is_running();
+            // Only a game that played all of its rounds or was concluded early can be
+            // ended. A game that is still forming or already finished can't.
+            let res = match self.state {
+                State::Running { .. } => !self.is_running(),
+                State::Ended { .. } => true,
+                State::Forming { .. } | State::Finished { .. } => false,
+            };
             res.then_some(())
                 .ok_or(GameError::GameCantBeEndedOrHasAlreadyEnded)?;
 
-            let players = self.players();
+            let players = self.players_sorted();
             let winner = players
-                .iter()
-                .min_by_key(|p| p.scoring_order())
-                .ok_or(GameError::WeOnlyAllowStartingTheGameWithAtLeastOnePlayer)?
-                .id;
-
-            // Give the pot to the winner
-            Self::env().transfer(
-                winner,
-                Balance::from(players.len() as u32).saturating_mul(self.buy_in),
-            )?;
+                .first()
+                .ok_or(GameError::WeOnlyAllowStartingTheGameWithAtLeastOnePlayer)?;
+
+            // Give the pot to the winner. Disqualified players can't win the pot. If
+            // every player was disqualified the pot stays in the contract and the
+            // opener takes the place of the winner. The rake goes to the treasury
+            // first. Then part of the rest is kept for the season of an arena and
+            // part of it carries over into the next game.
+            let mut pot = core::mem::take(&mut self.pot);
+            let rake = Self::share_of(pot, self.rake_bps);
+            if rake > 0 {
+                self.owe(self.treasury, rake);
+                pot = pot.saturating_sub(rake);
+                Self::env().emit_event(RakeCollected {
+                    treasury: self.treasury,
+                    amount: rake,
+                });
+            }
+            if let Some(arena) = self.arena {
+                let share = Self::share_of(pot, arena.season_share_bps);
+                self.season_pot = self.season_pot.saturating_add(share);
+                pot = pot.saturating_sub(share);
+            }
+            let rollover = Self::share_of(pot, self.rollover_bps);
+            self.rollover = self.rollover.saturating_add(rollover);
+            pot = pot.saturating_sub(rollover);
+            let (winner, paid) = if winner.status == PlayerStatus::Disqualified {
+                (self.opener, None)
+            } else {
+                // Player contracts might not be able to receive funds so the prize
+                // goes to the owner.
+                self.owe(winner.owner, pot);
+                (winner.id, Some(winner.id))
+            };
 
+            // Every player gets a series point for each player it outranked.
+            // Disqualified players get nothing. Arenas award the same season points.
+            let mut roster = self.players();
+            for (rank, player) in players.iter().enumerate() {
+                if player.status == PlayerStatus::Disqualified {
+                    continue;
+                }
+                let points = (players.len() - rank - 1) as u32;
+                if let Ok(idx) = Self::find_player(&player.id, &roster) {
+                    let entry = &mut roster[idx].series_points;
+                    *entry = entry.saturating_add(points);
+                }
+                if self.arena.is_some() {
+                    self.add_season_points(player.id, player.owner, points);
+                }
+            }
+            self.players.set(&roster);
+            self.series_games = self.series_games.saturating_add(1);
+
+            let result = GameResult {
+                standings: players
+                    .into_iter()
+                    .map(|player| {
+                        Standing {
+                            payout: if paid == Some(player.id) { pot } else { 0 },
+                            id: player.id,
+                            owner: player.owner,
+                            name: player.name,
+                            status: player.status,
+                            score: player.score,
+                            gas_used: player.gas_used,
+                        }
+                    })
+                    .collect(),
+                rounds_played: self.rounds_played(),
+                board_hash: self.board_hash(),
+            };
+            if let Some(trophies) = self.trophies {
+                self.mint_trophies(trophies, &result);
+            }
+            if let Some(ratings) = self.ratings {
+                Self::report_result(ratings, &result);
+            }
+            self.last_result.set(&result);
             self.state = State::Finished { winner };
             Self::env().emit_event(GameEnded {
                 ender: Self::env().caller(),
             });
+            if let Some(arena) = self.arena {
+                self.reopen_arena(arena.cooldown)?;
+            }
             Ok(())
         }
 
+        /// Bring a finished game back into the forming phase without any players.
+        ///
+        /// Registered players are removed and series points are reset. Only the
+        /// opener can do this.
         #[ink(message)]
         pub fn reset_game(&mut self) -> Result<(), GameError> {
-            match self.state {
-                State::Finished { .. } => {
-                    self.state = State::Forming {
-                        earliest_start: Self::env().block_number(),
-                    };
-                    for x in 0..self.dimensions.x {
-                        for y in 0..self.dimensions.y {
-                            self.board.remove(self.idx(&Field { x, y }).unwrap());
-                        }
-                    }
-                    self.players.set(&Vec::new());
-                    self.last_turn.set(&0);
-                    Ok(())
+            (Self::env().caller() == self.opener)
+                .then_some(())
+                .ok_or(GameError::OnlyAdminCanResetTheGame)?;
+            self.clear_game()?;
+            for player in self.players() {
+                if let Some(credit) = self.reentry_credit.take(player.id) {
+                    self.reentry_total = self.reentry_total.saturating_sub(credit);
+                    self.owe(player.owner, credit);
                 }
-                _ => Err(GameError::OnlyFinishedGameCanBeReset),
             }
+            self.players.set(&Vec::new());
+            self.series_games = 0;
+            Ok(())
+        }
+
+        /// Play another game with the same players.
+        ///
+        /// Like [`reset_game`] but the players stay registered and keep their series
+        /// points. Every player has to pay the buy-in again through
+        /// [`confirm_rematch`]. Players that don't confirm are removed when the game
+        /// starts. Only the opener can do this.
+        #[ink(message)]
+        pub fn rematch(&mut self) -> Result<(), GameError> {
+            (Self::env().caller() == self.opener)
+                .then_some(())
+                .ok_or(GameError::OnlyAdminCanStartARematch)?;
+            self.clear_game()?;
+
+            let mut players = self.players();
+            for player in players.iter_mut() {
+                player.prepare_rematch();
+            }
+            self.players.set(&players);
+            Self::env().emit_event(RematchStarted {
+                series_games: self.series_games,
+            });
+            Ok(())
+        }
+
+        /// Take part in the rematch by paying the buy-in again.
+        ///
+        /// Can be called by the owner of the player or the player itself.
+        #[ink(message, payable)]
+        pub fn confirm_rematch(&mut self, player: AccountId) -> Result<(), GameError> {
+            matches!(self.state, State::Forming { .. })
+                .then_some(())
+                .ok_or(GameError::RematchCanOnlyBeConfirmedInTheFormingPhase)?;
+
+            let mut players = self.players();
+            let idx = Self::find_player(&player, &players)
+                .map_err(|_| GameError::PlayerIsNotRegistered)?;
+            let player = &mut players[idx];
+
+            let caller = Self::env().caller();
+            (caller == player.owner || caller == player.id)
+                .then_some(())
+                .ok_or(GameError::OnlyOwnerCanConfirmRematch)?;
+            (player.status == PlayerStatus::Unconfirmed)
+                .then_some(())
+                .ok_or(GameError::RematchIsAlreadyConfirmed)?;
+            self.collect_buy_in()?;
+
+            player.status = PlayerStatus::Active;
+            self.pot = self.pot.saturating_add(self.buy_in);
+            Self::env().emit_event(RematchConfirmed { player: player.id });
+            self.players.set(&players);
+            Ok(())
+        }
+
+        /// Call `candidate` with a sample [`GameInfo`] and check that it returns a turn.
+        ///
+        /// All state changes are reverted. The game uses this to vet players on
+        /// registration when [`RegistrationCheck::DryCall`] is configured.
+        #[ink(message)]
+        pub fn conformance_check(
+            &mut self,
+            candidate: AccountId,
+        ) -> Result<(), GameError> {
+            let players = self.players();
+            let gas_limit = Self::calc_gas_limit(players.len().saturating_add(1));
+            let game_info = GameInfo {
+                rounds_played: 0,
+                gas_left: Self::calc_gas_budget(gas_limit, self.rounds),
+                player_scores: players
+                    .iter()
+                    .map(|player| (player.name.clone(), player.score))
+                    .collect(),
+            };
+            let result = match Self::invoke_player(candidate, &game_info, gas_limit) {
+                (Ok(Ok(_)), _) => Ok(()),
+                (_err, _) => {
+                    debug_println!("Conformance check failed: {:?}", _err);
+                    Err(GameError::PlayerFailedConformanceCheck)
+                }
+            };
+            Self::revert(&result)
+        }
+
+        /// Dry-run the turn of `player` against the current state of the game.
+        ///
+        /// The player is called just like in [`submit_turn`] but all state changes are
+        /// reverted. This can be called in every state of the game and also for
+        /// players that aren't registered yet.
+        #[ink(message)]
+        pub fn preview_turn(&mut self, player: AccountId) -> TurnPreview {
+            let players = self.players();
+            let mut game_info = GameInfo {
+                rounds_played: self.rounds_played(),
+                gas_left: 0,
+                player_scores: players
+                    .iter()
+                    .map(|player| (player.name.clone(), player.score))
+                    .collect(),
+            };
+            let num_players = players.len();
+            let (mut player, num_players) = match Self::find_player(&player, &players) {
+                Ok(idx) => (players.into_iter().nth(idx).unwrap(), num_players),
+                Err(_) => {
+                    let player = Player::new(
+                        player,
+                        String::new(),
+                        Self::env().caller(),
+                        PlayerKind::Contract,
+                    );
+                    (player, num_players.saturating_add(1))
+                }
+            };
+            let (outcome, gas_used) =
+                self.play_turn(&mut player, &mut game_info, num_players, None);
+            Self::revert(&TurnPreview {
+                turn: outcome.turn(),
+                gas_used,
+                outcome,
+            })
         }
 
         /// Add a new player to the game. Only allowed while the game has not started.
@@ -246,6 +780,76 @@ mod contract {
             &mut self,
             id: AccountId,
             name: String,
+        ) -> Result<(), GameError> {
+            self.register(id, name, PlayerKind::Contract)
+        }
+
+        /// Register the caller as a human player.
+        ///
+        /// Instead of being called every round human players queue their moves in
+        /// advance using [`queue_move`]. Only allowed while the game has not started.
+        #[ink(message, payable)]
+        pub fn register_human(&mut self, name: String) -> Result<(), GameError> {
+            self.register(Self::env().caller(), name, PlayerKind::Human)
+        }
+
+        /// Add a new player with the name the caller reserved in the registry.
+        ///
+        /// Only allowed while the game has not started.
+        #[ink(message, payable)]
+        pub fn register_player_with_identity(
+            &mut self,
+            id: AccountId,
+        ) -> Result<(), GameError> {
+            let name = self.identity_name(Self::env().caller())?;
+            self.register(id, name, PlayerKind::Contract)
+        }
+
+        /// Register the caller as a human player with the name it reserved in the
+        /// registry.
+        ///
+        /// Only allowed while the game has not started.
+        #[ink(message, payable)]
+        pub fn register_human_with_identity(&mut self) -> Result<(), GameError> {
+            let caller = Self::env().caller();
+            let name = self.identity_name(caller)?;
+            self.register(caller, name, PlayerKind::Human)
+        }
+
+        /// Queue a move for the calling human player.
+        ///
+        /// One move is taken from the queue whenever it is the player's turn.
+        #[ink(message)]
+        pub fn queue_move(&mut self, turn: Field) -> Result<(), GameError> {
+            let caller = Self::env().caller();
+            let players = self.players();
+            Self::find_player(&caller, &players)
+                .ok()
+                .filter(|&idx| players[idx].kind == PlayerKind::Human)
+                .ok_or(GameError::OnlyHumanPlayersCanQueueMoves)?;
+
+            let mut queue = self.move_queues.get(caller).unwrap_or_default();
+            queue
+                .len()
+                .lt(&MOVE_QUEUE_LIMIT)
+                .then_some(())
+                .ok_or(GameError::MoveQueueIsFull)?;
+            queue.push(turn);
+            self.move_queues.insert(caller, &queue);
+            Ok(())
+        }
+
+        /// The moves a human player queued that are yet to be played.
+        #[ink(message)]
+        pub fn queued_moves(&self, player: AccountId) -> Vec<Field> {
+            self.move_queues.get(player).unwrap_or_default()
+        }
+
+        fn register(
+            &mut self,
+            id: AccountId,
+            name: String,
+            kind: PlayerKind,
         ) -> Result<(), GameError> {
             matches!(self.state, State::Forming { .. })
                 .then_some(())
@@ -256,10 +860,14 @@ mod contract {
                 .then_some(())
                 .ok_or(GameError::InvalidLengthForName)?;
 
-            self.buy_in
-                .eq(&Self::env().transferred_value())
-                .then_some(())
-                .ok_or(GameError::WrongBuyIn)?;
+            if let Some(registry) = self.registry {
+                Self::registry_owner_of(registry, &name)?
+                    .map_or(true, |owner| owner == Self::env().caller())
+                    .then_some(())
+                    .ok_or(GameError::NameIsReservedByAnotherAccount)?;
+            }
+
+            self.collect_buy_in()?;
 
             let mut players = self.players();
 
@@ -274,16 +882,16 @@ mod contract {
                     let res = !players.iter().any(|p| p.name == name);
                     res.then_some(()).ok_or(GameError::ThisNameIsAlreadyTaken)?;
 
+                    if kind == PlayerKind::Contract {
+                        self.check_registration(id)?;
+                    }
+
                     players.insert(
                         idx,
-                        Player {
-                            id,
-                            name,
-                            gas_used: 0,
-                            score: 0,
-                        },
+                        Player::new(id, name, Self::env().caller(), kind),
                     );
                     self.players.set(&players);
+                    self.pot = self.pot.saturating_add(self.buy_in);
                     Self::env().emit_event(PlayerRegistered { player: id });
                 }
                 Ok(_) => {
@@ -293,49 +901,248 @@ mod contract {
             Ok(())
         }
 
-        /// This is the actual game loop.
+        /// Keep `player` registered when the arena reopens.
         ///
-        /// It can be called by anyone and triggers at most one turn
-        /// of the game per block.
-        #[ink(message)]
-        pub fn submit_turn(&mut self) -> Result<(), GameError> {
-            self.is_running()
+        /// The transferred value is added to the credit the buy-ins of the next games
+        /// are taken from. In a token game `amount` is pulled from the caller instead.
+        /// Disabling auto-reentry refunds the credit to the owner. Can be called by the
+        /// owner of the player or the player itself.
+        #[ink(message, payable)]
+        pub fn set_auto_reentry(
+            &mut self,
+            player: AccountId,
+            enabled: bool,
+            amount: Balance,
+        ) -> Result<(), GameError> {
+            self.ensure_not_in_round()?;
+            let players = self.players();
+            let idx = Self::find_player(&player, &players)
+                .map_err(|_| GameError::PlayerIsNotRegistered)?;
+            let player = &players[idx];
+
+            let caller = Self::env().caller();
+            (caller == player.owner || caller == player.id)
                 .then_some(())
-                .ok_or(GameError::GameCannotBeEndedOrHasAlreadyEnded)?;
+                .ok_or(GameError::OnlyOwnerCanChangeAutoReentry)?;
+
+            let deposit = self.collect(amount)?;
+            let credit = self
+                .reentry_credit
+                .take(player.id)
+                .unwrap_or_default()
+                .saturating_add(deposit);
+            if enabled {
+                self.reentry_credit.insert(player.id, &credit);
+                self.reentry_total = self.reentry_total.saturating_add(deposit);
+            } else if credit > 0 {
+                self.reentry_total = self
+                    .reentry_total
+                    .saturating_add(deposit)
+                    .saturating_sub(credit);
+                self.pay(player.owner, credit)?;
+            }
+            Ok(())
+        }
 
-            let mut players = self.players();
+        /// The credit of `player` if it opted into auto-reentry.
+        #[ink(message)]
+        pub fn reentry_credit(&self, player: AccountId) -> Option<Balance> {
+            self.reentry_credit.get(player)
+        }
 
-            let State::Running { rounds_played } = &mut self.state else {
-                return Err(GameError::ThisGameDoesNotAcceptTurnsRightNow);
-            };
+        /// Award the season pot to the owner of the player with the most season
+        /// points.
+        ///
+        /// The owner collects it with [`claim_prize`]. This starts a new season. Only
+        /// the opener can do this.
+        #[ink(message)]
+        pub fn end_season(&mut self) -> Result<(), GameError> {
+            self.ensure_not_in_round()?;
+            (Self::env().caller() == self.opener)
+                .then_some(())
+                .ok_or(GameError::OnlyAdminCanEndTheSeason)?;
+
+            let winner = self
+                .season_standings()
+                .first()
+                .map(|(id, _)| *id)
+                .ok_or(GameError::NoSeasonToEnd)?;
+            let (owner, _) = self
+                .season_points
+                .get(winner)
+                .ok_or(GameError::NoSeasonToEnd)?;
+            let payout = self.season_pot;
+            // Player contracts might not be able to receive funds so the pot goes to
+            // the owner.
+            self.owe(owner, payout);
+            for index in 0..self.season_len {
+                if let Some(player) = self.season_players.take(index) {
+                    self.season_points.remove(player);
+                }
+            }
+            self.season_len = 0;
+            self.season_pot = 0;
+            Self::env().emit_event(SeasonEnded { winner, payout });
+            Ok(())
+        }
 
-            // Only one turn per block
-            // We need to write this to storage because of reentrancy: The called contract
-            // could call this function again and do another turn in the same block.
-            let current_block = Self::env().block_number();
-            let last_turn = self
-                .last_turn
-                .get()
-                .ok_or(GameError::ValueWasNotSetWhenStartingTheGame)?;
+        /// Season points of all players that finished an arena game, highest first.
+        #[ink(message)]
+        pub fn season_standings(&self) -> Vec<(AccountId, u32)> {
+            let mut standings: Vec<_> = (0..self.season_len)
+                .filter_map(|index| {
+                    let player = self.season_players.get(index)?;
+                    Some((player, self.season_points.get(player)?.1))
+                })
+                .collect();
+            standings.sort_by_key(|(_, points)| Reverse(*points));
+            standings
+        }
 
-            last_turn
-                .lt(&current_block)
-                .then_some(())
-                .ok_or(GameError::TurnWasAlreadySubmittedForThisBlock)?;
+        /// The part of the pots that is paid out when the season ends.
+        #[ink(message)]
+        pub fn season_pot(&self) -> Balance {
+            self.season_pot
+        }
 
-            self.last_turn.set(&current_block);
+        /// Leave the game for good.
+        ///
+        /// Can be called by the owner of the player or the player itself. A resigned
+        /// player isn't called anymore and ranks below all active players. Depending on
+        /// [`release_fields_on_resign`] its fields are released back to the board.
+        #[ink(message)]
+        pub fn resign(&mut self, player: AccountId) -> Result<(), GameError> {
+            self.ensure_not_in_round()?;
+            matches!(self.state, State::Forming { .. } | State::Running { .. })
+                .then_some(())
+                .ok_or(GameError::PlayersCanOnlyResignWhileTheGameIsOn)?;
 
-            // We need to cache this as we can't accessed players in the loop.
-            let num_players = players.len();
+            let mut players = self.players();
+            let idx = Self::find_player(&player, &players)
+                .map_err(|_| GameError::PlayerIsNotRegistered)?;
+            let player = &mut players[idx];
 
-            // Batching is needed so we don't call all the players every round
-            // (because of the gas limit).
-            let current_round = *rounds_played;
-            *rounds_played = rounds_played.saturating_add(1);
-            let num_batches = Self::calc_num_batches(num_players);
-            let current_batch = current_round.rem_euclid(num_batches);
+            let caller = Self::env().caller();
+            (caller == player.owner || caller == player.id)
+                .then_some(())
+                .ok_or(GameError::OnlyOwnerCanResignPlayer)?;
+            player
+                .is_active()
+                .then_some(())
+                .ok_or(GameError::PlayerHasAlreadyLeftTheGame)?;
 
-            // Information about the game is passed to players.
+            player.status = PlayerStatus::Resigned;
+            let fields_released = self.release_fields_on_resign;
+            if fields_released {
+                self.release_fields(player);
+            }
+            Self::env().emit_event(PlayerResigned {
+                player: player.id,
+                fields_released,
+            });
+            self.players.set(&players);
+            Ok(())
+        }
+
+        /// Take `amount` points away from `player`.
+        ///
+        /// Only the referee can do this and needs to give a reason.
+        #[ink(message)]
+        pub fn deduct_score(
+            &mut self,
+            player: AccountId,
+            amount: u64,
+            reason: String,
+        ) -> Result<(), GameError> {
+            self.penalize(player, reason, |_, player| {
+                player.score = player.score.saturating_sub(amount);
+                PenaltyAction::DeductScore { amount }
+            })
+        }
+
+        /// Let `player` sit out the next `rounds` rounds.
+        ///
+        /// Only the referee can do this and needs to give a reason.
+        #[ink(message)]
+        pub fn freeze_player(
+            &mut self,
+            player: AccountId,
+            rounds: u32,
+            reason: String,
+        ) -> Result<(), GameError> {
+            self.penalize(player, reason, |game, player| {
+                player.frozen_until = game.rounds_played().saturating_add(rounds);
+                PenaltyAction::Freeze { rounds }
+            })
+        }
+
+        /// Release the fields of `player` inside the region spanned by `from` and
+        /// `to` (inclusive) back to the board.
+        ///
+        /// Only the referee can do this and needs to give a reason.
+        #[ink(message)]
+        pub fn strip_fields(
+            &mut self,
+            player: AccountId,
+            from: Field,
+            to: Field,
+            reason: String,
+        ) -> Result<(), GameError> {
+            self.penalize(player, reason, |game, player| {
+                let released = game.release_fields_in(player, from, to);
+                PenaltyAction::StripFields { from, to, released }
+            })
+        }
+
+        /// All penalties the referee gave so far.
+        #[ink(message)]
+        pub fn audit_log(&self) -> Vec<Penalty> {
+            (0..self.audit_log_len)
+                .filter_map(|idx| self.audit_log.get(idx))
+                .collect()
+        }
+
+        /// The account that is allowed to penalize players.
+        #[ink(message)]
+        pub fn referee(&self) -> AccountId {
+            self.referee
+        }
+
+        /// Change the account that is allowed to penalize players.
+        ///
+        /// Only the opener can do this.
+        #[ink(message)]
+        pub fn set_referee(&mut self, referee: AccountId) -> Result<(), GameError> {
+            self.ensure_not_in_round()?;
+            (Self::env().caller() == self.opener)
+                .then_some(())
+                .ok_or(GameError::OnlyAdminCanConfigureTheGame)?;
+            self.referee = referee;
+            Ok(())
+        }
+
+        /// This is the actual game loop.
+        ///
+        /// It can be called by anyone and triggers at most one turn
+        /// of the game per block.
+        #[ink(message)]
+        pub fn submit_turn(&mut self) -> Result<(), GameError> {
+            (self.turn_mode == TurnMode::Calls)
+                .then_some(())
+                .ok_or(GameError::GameOnlyAcceptsSignedTurns)?;
+
+            let current_round = self.begin_round()?;
+            let mut players = self.players();
+
+            // We need to cache this as we can't accessed players in the loop.
+            let num_players = players.len();
+
+            // Batching is needed so we don't call all the players every round
+            // (because of the gas limit).
+            let num_batches = Self::calc_num_batches(num_players);
+            let current_batch = current_round.rem_euclid(num_batches);
+
+            // Information about the game is passed to players.
             let mut game_info = GameInfo {
                 rounds_played: current_round,
                 gas_left: 0,
@@ -345,88 +1152,86 @@ mod contract {
                     .collect(),
             };
 
+            let mut log = RoundLog::default();
             for (idx, player) in players.iter_mut().enumerate() {
                 if (idx as u32).rem_euclid(num_batches) != current_batch {
                     continue;
                 }
 
-                // Stop calling a contract that has no gas left.
-                let gas_limit = Self::calc_gas_limit(num_players);
-                let gas_left = Self::calc_gas_budget(gas_limit, self.rounds)
-                    .saturating_sub(player.gas_used);
-                if gas_left == 0 {
-                    Self::env().emit_event(TurnTaken {
-                        player: player.id,
-                        outcome: TurnOutcome::BudgetExhausted,
-                    });
+                if !player.is_active() {
                     continue;
                 }
-                game_info.gas_left = gas_left;
 
-                // We need to call with reentrancy enabled to allow those
-                // contracts to query us.
-                let call = build_call::<DefaultEnvironment>()
-                    .call_type(Call::new(player.id))
-                    .gas_limit(gas_limit)
-                    .exec_input(
-                        ExecutionInput::new(Selector::from([0x00; 4]))
-                            .push_arg(&game_info),
-                    )
-                    .call_flags(CallFlags::default().set_allow_reentry(true))
-                    .returns::<Option<Field>>();
-
-                let gas_before = Self::env().gas_left();
-                let turn = call.try_invoke();
-                let gas_used = gas_before.saturating_sub(Self::env().gas_left());
-
-                // We continue even if the contract call fails. If the contract
-                // doesn't conform it is the players fault. No second tries.
-                let outcome = match turn {
-                    Ok(Ok(Some(turn))) if self.idx(&turn).is_some() => {
-                        let idx = self.idx(&turn).unwrap();
-                        // Player tried to make a turn: charge gas.
-                        player.gas_used = player.gas_used.saturating_add(gas_used);
-                        if !self.is_valid_coord(&turn) {
-                            TurnOutcome::OutOfBounds { turn }
-                        } else if let Some(entry) = self.board.get(idx) {
-                            TurnOutcome::Occupied {
-                                turn,
-                                player: entry.owner,
-                            }
-                        } else {
-                            self.board.insert(
-                                idx,
-                                &FieldEntry {
-                                    owner: player.id,
-                                    claimed_at: current_round,
-                                },
-                            );
-                            player.score = player.score.saturating_add(u64::from(
-                                current_round.saturating_add(1),
-                            ));
-                            TurnOutcome::Success { turn }
-                        }
-                    }
-                    Ok(Ok(None)) => TurnOutcome::NoTurn,
-                    _err => {
-                        // Player gets charged gas for failing.
-                        player.gas_used = player.gas_used.saturating_add(gas_used);
-                        debug_println!("Contract failed to make a turn: {:?}", _err);
-                        TurnOutcome::BrokenPlayer
-                    }
+                let (outcome, gas_charged) =
+                    self.play_turn(player, &mut game_info, num_players, None);
+                self.finish_turn(player, idx, outcome, gas_charged, &mut log);
+            }
+
+            self.end_round(current_round, &players, log);
+            self.pay_keeper();
+            Ok(())
+        }
+
+        /// Play a round with moves signed off-chain by the owners of the players.
+        ///
+        /// Only available in [`TurnMode::SignedMoves`] and only the relayer can do this
+        /// at most once per block. Each move must be signed for this game and the
+        /// current round. Every player can make at most one move per round and
+        /// players without a move in `moves` sit the round out. Moves for unknown
+        /// players, duplicate moves and moves with a bad signature are skipped.
+        #[ink(message)]
+        pub fn submit_signed_turns(
+            &mut self,
+            moves: Vec<SignedMove>,
+        ) -> Result<(), GameError> {
+            (self.turn_mode == TurnMode::SignedMoves)
+                .then_some(())
+                .ok_or(GameError::GameDoesNotAcceptSignedTurns)?;
+            (Self::env().caller() == self.relayer)
+                .then_some(())
+                .ok_or(GameError::OnlyRelayerCanSubmitSignedTurns)?;
+
+            let current_round = self.begin_round()?;
+            let mut players = self.players();
+            let num_players = players.len();
+
+            let mut game_info = GameInfo {
+                rounds_played: current_round,
+                gas_left: 0,
+                player_scores: players
+                    .iter()
+                    .map(|player| (player.name.clone(), player.score))
+                    .collect(),
+            };
+
+            let mut log = RoundLog::default();
+            let mut moved = Vec::with_capacity(moves.len());
+            for signed in moves {
+                let Ok(idx) = Self::find_player(&signed.player, &players) else {
+                    continue;
                 };
+                if moved.contains(&idx) {
+                    continue;
+                }
 
-                Self::env().emit_event(TurnTaken {
-                    player: player.id,
-                    outcome,
-                });
+                let player = &mut players[idx];
+                if !player.is_active()
+                    || !self.verify_signed_move(&signed, &player.owner, current_round)
+                {
+                    continue;
+                }
+                moved.push(idx);
+                let (outcome, gas_charged) = self.play_turn(
+                    player,
+                    &mut game_info,
+                    num_players,
+                    Some(signed.turn),
+                );
+                self.finish_turn(player, idx, outcome, gas_charged, &mut log);
             }
 
-            Self::env().emit_event(RoundIncremented {
-                rounds_played: current_round.saturating_add(1),
-            });
-
-            self.players.set(&players);
+            self.end_round(current_round, &players, log);
+            self.pay_keeper();
             Ok(())
         }
 
@@ -463,96 +1268,1973 @@ mod contract {
             Self::calc_gas_budget(self.gas_limit(), self.rounds)
         }
 
-        /// The current game state.
+        /// How player contracts are vetted when they are registered.
         #[ink(message)]
-        pub fn state(&self) -> State {
-            self.state.clone()
+        pub fn registration_check(&self) -> RegistrationCheck {
+            self.registration_check
         }
 
-        /// Returns `true` if the game is running.
+        /// Change how player contracts are vetted when they are registered.
+        ///
+        /// Only the opener can do this and only before the game started.
         #[ink(message)]
-        pub fn is_running(&self) -> bool {
-            if let State::Running { rounds_played, .. } = self.state {
-                rounds_played < self.rounds
+        pub fn set_registration_check(
+            &mut self,
+            check: RegistrationCheck,
+        ) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            self.registration_check = check;
+            Ok(())
+        }
+
+        /// Returns `true` if contracts with `code_hash` can be registered when
+        /// [`RegistrationCheck::CodeHashAllowlist`] is configured.
+        #[ink(message)]
+        pub fn is_code_hash_allowed(&self, code_hash: Hash) -> bool {
+            self.allowed_code_hashes.contains(code_hash)
+        }
+
+        /// Add `code_hash` to or remove it from the allowlist.
+        ///
+        /// Only the opener can do this and only before the game started.
+        #[ink(message)]
+        pub fn set_code_hash_allowed(
+            &mut self,
+            code_hash: Hash,
+            allowed: bool,
+        ) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            if allowed {
+                self.allowed_code_hashes.insert(code_hash, &());
             } else {
-                false
+                self.allowed_code_hashes.remove(code_hash);
             }
+            Ok(())
         }
 
-        /// List of all players sorted by score and gas costs.
+        /// Whether the fields of a player are released when it resigns.
         #[ink(message)]
-        pub fn players_sorted(&self) -> Vec<Player> {
-            let mut players = self.players();
-            players.sort_unstable_by_key(|player| player.scoring_order());
-            players
+        pub fn release_fields_on_resign(&self) -> bool {
+            self.release_fields_on_resign
         }
 
-        /// Returns the dimensions of the board.
+        /// Change whether the fields of a player are released when it resigns.
+        ///
+        /// Only the opener can do this and only before the game started.
         #[ink(message)]
-        pub fn dimensions(&self) -> Field {
-            self.dimensions
+        pub fn set_release_fields_on_resign(
+            &mut self,
+            release: bool,
+        ) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            self.release_fields_on_resign = release;
+            Ok(())
         }
 
-        /// Returns the value (owner) of the supplied field.
+        /// After how many broken or out of bounds turns in a row a player is
+        /// disqualified. `None` if players are never disqualified.
         #[ink(message)]
-        pub fn field(&self, coord: Field) -> Option<FieldEntry> {
-            self.idx(&coord).and_then(|idx| self.board.get(idx))
+        pub fn strike_limit(&self) -> Option<u32> {
+            self.strike_limit
         }
 
-        /// Returns the complete board.
+        /// Change after how many broken or out of bounds turns in a row a player is
+        /// disqualified and whether its fields are released back to the board. The
+        /// limit must be at least one.
         ///
-        /// The index into the vector is calculated as `x + y * width`.
+        /// Only the opener can do this and only before the game started.
         #[ink(message)]
-        pub fn board(&self) -> Vec<Option<FieldEntry>> {
-            self.board_iter().collect()
+        pub fn set_strike_limit(
+            &mut self,
+            limit: Option<u32>,
+            release_fields: bool,
+        ) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            (limit != Some(0))
+                .then_some(())
+                .ok_or(GameError::StrikeLimitMustBeAtLeastOne)?;
+            self.strike_limit = limit;
+            self.release_fields_on_disqualification = release_fields;
+            Ok(())
         }
 
-        fn calc_gas_limit(num_players: usize) -> u64 {
-            (GAS_LIMIT_ALL_PLAYERS
-                .saturating_mul(u64::from(Self::calc_num_batches(num_players))))
-                .checked_div(num_players as u64)
-                .unwrap_or(0)
+        /// The buy-ins of the current game that will be paid out to the winner.
+        #[ink(message)]
+        pub fn pot(&self) -> Balance {
+            self.pot
         }
 
-        fn calc_num_batches(num_players: usize) -> u32 {
-            if num_players > 30 {
-                2
-            } else {
-                1
+        /// Add the transferred value to the pot of the current game.
+        ///
+        /// In a token game `amount` is pulled from the caller instead. Anyone can do
+        /// this until the game ends. The sponsorship and `message` are listed by
+        /// [`sponsors`].
+        #[ink(message, payable)]
+        pub fn sponsor(
+            &mut self,
+            message: String,
+            amount: Balance,
+        ) -> Result<(), GameError> {
+            self.ensure_not_in_round()?;
+            matches!(self.state, State::Forming { .. } | State::Running { .. })
+                .then_some(())
+                .ok_or(GameError::PotCanOnlyBeSponsoredBeforeTheGameEnded)?;
+            ALLOWED_MESSAGE_SIZES
+                .contains(&message.len())
+                .then_some(())
+                .ok_or(GameError::InvalidLengthForMessage)?;
+            let mut sponsors = self.sponsors.get().unwrap_or_default();
+            sponsors
+                .len()
+                .lt(&SPONSOR_LIMIT)
+                .then_some(())
+                .ok_or(GameError::MaximumSponsorCountReached)?;
+            let amount = self.collect(amount)?;
+            (amount > 0)
+                .then_some(())
+                .ok_or(GameError::SponsorshipNeedsValue)?;
+            let sponsor = Self::env().caller();
+            sponsors.push(Sponsorship {
+                sponsor,
+                amount,
+                message: message.clone(),
+            });
+            self.sponsors.set(&sponsors);
+            self.pot = self.pot.saturating_add(amount);
+            Self::env().emit_event(Sponsored {
+                sponsor,
+                amount,
+                message,
+            });
+            Ok(())
+        }
+
+        /// Everyone who added to the pot of the current game.
+        #[ink(message)]
+        pub fn sponsors(&self) -> Vec<Sponsorship> {
+            self.sponsors.get().unwrap_or_default()
+        }
+
+        /// The share of the pot in basis points that carries over into the next game.
+        #[ink(message)]
+        pub fn rollover_bps(&self) -> u16 {
+            self.rollover_bps
+        }
+
+        /// Carry `rollover_bps` basis points of the pot over into the next game.
+        ///
+        /// Only the opener can do this and only before the game started.
+        #[ink(message)]
+        pub fn set_rollover(&mut self, rollover_bps: u16) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            rollover_bps
+                .le(&MAX_BASIS_POINTS)
+                .then_some(())
+                .ok_or(GameError::InvalidShare)?;
+            self.rollover_bps = rollover_bps;
+            Ok(())
+        }
+
+        /// Transfer everything `account` is owed to it.
+        ///
+        /// Anyone can trigger the payout but the prize always goes to `account`.
+        #[ink(message)]
+        pub fn claim_prize(&mut self, account: AccountId) -> Result<(), GameError> {
+            self.ensure_not_in_round()?;
+            let amount = self
+                .prizes
+                .get(account)
+                .ok_or(GameError::NoPrizeToClaim)?;
+            self.pay(account, amount)?;
+            self.prizes.remove(account);
+            self.prizes_total = self.prizes_total.saturating_sub(amount);
+            Self::env().emit_event(PrizeClaimed { account, amount });
+            Ok(())
+        }
+
+        /// Owe the unused keeper fund and the rollover to the treasury.
+        ///
+        /// Nobody else can claim them once no further game is played. They need to
+        /// be paid out before the winner can [`destroy`] the contract. Only the opener
+        /// can do this and only when the game is finished.
+        #[ink(message)]
+        pub fn release_reserves(&mut self) -> Result<(), GameError> {
+            (Self::env().caller() == self.opener)
+                .then_some(())
+                .ok_or(GameError::OnlyAdminCanConfigureTheGame)?;
+            matches!(self.state, State::Finished { .. })
+                .then_some(())
+                .ok_or(GameError::OnlyFinishedGamesCanBeDestroyed)?;
+
+            let released = core::mem::take(&mut self.keeper_fund)
+                .saturating_add(core::mem::take(&mut self.rollover));
+            if released > 0 {
+                self.owe(self.treasury, released);
             }
+            Ok(())
         }
 
-        fn calc_gas_budget(gas_limit: u64, num_rounds: u32) -> u64 {
-            gas_limit.saturating_mul(u64::from(num_rounds).saturating_div(4))
+        /// The amount `account` is owed and can collect with [`claim_prize`].
+        #[ink(message)]
+        pub fn unclaimed_prize(&self, account: AccountId) -> Balance {
+            self.prizes.get(account).unwrap_or_default()
         }
 
-        fn players(&self) -> Vec<Player> {
-            self.players
-                .get()
-                .expect("Initial value is set in constructor.")
+        /// The share of the pot in basis points that goes to the treasury.
+        #[ink(message)]
+        pub fn rake_bps(&self) -> u16 {
+            self.rake_bps
         }
 
-        fn board_iter(&self) -> impl Iterator<Item=Option<FieldEntry>> + '_ {
-            (0..self.dimensions.y).flat_map(move |y| {
-                (0..self.dimensions.x).map(move |x| self.field(Field { x, y }))
-            })
+        /// The account that collects the rake and leftover balance.
+        #[ink(message)]
+        pub fn treasury(&self) -> AccountId {
+            self.treasury
         }
 
-        fn find_player(id: &AccountId, players: &[Player]) -> Result<usize, usize> {
-            players.binary_search_by_key(id, |player| player.id)
+        /// Take `rake_bps` basis points of the pot for `treasury` when the game ends.
+        ///
+        /// Only the opener can do this and only before the game started.
+        #[ink(message)]
+        pub fn set_rake(
+            &mut self,
+            rake_bps: u16,
+            treasury: AccountId,
+        ) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            rake_bps
+                .le(&MAX_BASIS_POINTS)
+                .then_some(())
+                .ok_or(GameError::InvalidShare)?;
+            self.rake_bps = rake_bps;
+            self.treasury = treasury;
+            Ok(())
         }
 
-        fn idx(&self, coord: &Field) -> Option<u32> {
-            coord
-                .y
-                .checked_mul(self.dimensions.x)
-                .and_then(|val| val.checked_add(coord.x))
+        /// Send all balance that isn't owed to anyone to the treasury.
+        ///
+        /// This collects rounding dust, pots nobody could win and the storage
+        /// deposits refunded after a reset. Only the treasury can do this.
+        #[ink(message)]
+        pub fn sweep(&mut self) -> Result<(), GameError> {
+            self.ensure_not_in_round()?;
+            (Self::env().caller() == self.treasury)
+                .then_some(())
+                .ok_or(GameError::OnlyTreasuryCanSweep)?;
+
+            let amount = self.free_balance()?.saturating_sub(self.reserved());
+            (amount > 0).then_some(()).ok_or(GameError::NothingToSweep)?;
+
+            self.pay(self.treasury, amount)?;
+            Self::env().emit_event(Swept {
+                treasury: self.treasury,
+                amount,
+            });
+            Ok(())
         }
 
-        fn is_valid_coord(&self, coord: &Field) -> bool {
-            self.idx(coord)
-                .map(|val| val < self.dimensions.len())
-                .unwrap_or(false)
+        /// The keeper configuration if whoever advances a round is paid.
+        #[ink(message)]
+        pub fn keeper(&self) -> Option<KeeperConfig> {
+            self.keeper
+        }
+
+        /// Pay whoever advances a round or stop doing so.
+        ///
+        /// Only the opener can do this and only before the game started.
+        #[ink(message)]
+        pub fn set_keeper(
+            &mut self,
+            keeper: Option<KeeperConfig>,
+        ) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            if let Some(keeper) = keeper {
+                keeper
+                    .pot_share_bps
+                    .le(&MAX_BASIS_POINTS)
+                    .then_some(())
+                    .ok_or(GameError::InvalidShare)?;
+            }
+            self.keeper = keeper;
+            Ok(())
+        }
+
+        /// The balance keeper fees are paid from.
+        #[ink(message)]
+        pub fn keeper_fund(&self) -> Balance {
+            self.keeper_fund
+        }
+
+        /// Add the transferred value to the keeper fund.
+        ///
+        /// In a token game `amount` is pulled from the caller instead. Only possible
+        /// until the game ends.
+        #[ink(message, payable)]
+        pub fn fund_keepers(&mut self, amount: Balance) -> Result<(), GameError> {
+            self.ensure_not_in_round()?;
+            matches!(self.state, State::Forming { .. } | State::Running { .. })
+                .then_some(())
+                .ok_or(GameError::KeeperFundCanOnlyBeToppedUpBeforeTheGameEnded)?;
+            let amount = self.collect(amount)?;
+            self.keeper_fund = self.keeper_fund.saturating_add(amount);
+            Ok(())
+        }
+
+        /// The PSP22 token buy-ins and prizes are paid in or `None` for the native
+        /// currency.
+        #[ink(message)]
+        pub fn buy_in_token(&self) -> Option<AccountId> {
+            self.buy_in_token
+        }
+
+        /// Pay buy-ins and prizes in the PSP22 `token` or in the native currency.
+        ///
+        /// Players approve the game to spend the buy-in before registering. Value
+        /// can't be deposited into the keeper fund, reentry credits or the pot of a
+        /// game paid in a token. Only the opener can do this, only before the game
+        /// started and only while nothing is owed to anyone.
+        #[ink(message)]
+        pub fn set_buy_in_token(
+            &mut self,
+            token: Option<AccountId>,
+        ) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            (self.reserved() == 0)
+                .then_some(())
+                .ok_or(GameError::CurrencyCanOnlyBeChangedWhileNothingIsOwed)?;
+            self.buy_in_token = token;
+            Ok(())
+        }
+
+        /// The trophy configuration if top players get a trophy.
+        #[ink(message)]
+        pub fn trophies(&self) -> Option<TrophyConfig> {
+            self.trophies
+        }
+
+        /// Mint trophies to the owners of the top players when the game ends.
+        ///
+        /// Only the opener can do this and only before the game started.
+        #[ink(message)]
+        pub fn set_trophies(
+            &mut self,
+            trophies: Option<TrophyConfig>,
+        ) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            if let Some(trophies) = trophies {
+                trophies
+                    .count
+                    .le(&TROPHY_LIMIT)
+                    .then_some(())
+                    .ok_or(GameError::TooManyTrophies)?;
+            }
+            self.trophies = trophies;
+            Ok(())
+        }
+
+        /// The ratings contract the result is reported to when the game ends.
+        #[ink(message)]
+        pub fn ratings(&self) -> Option<AccountId> {
+            self.ratings
+        }
+
+        /// Report the result to the `ratings` contract when the game ends.
+        ///
+        /// The code hash of the game needs to be allowed by the ratings contract.
+        /// Only the opener can do this and only before the game started.
+        #[ink(message)]
+        pub fn set_ratings(
+            &mut self,
+            ratings: Option<AccountId>,
+        ) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            self.ratings = ratings;
+            Ok(())
+        }
+
+        /// The identity registry names are checked against.
+        #[ink(message)]
+        pub fn registry(&self) -> Option<AccountId> {
+            self.registry
+        }
+
+        /// Check names against the identity `registry`.
+        ///
+        /// Names reserved in the registry can then only be used by their owner.
+        /// Only the opener can do this and only before the game started.
+        #[ink(message)]
+        pub fn set_registry(
+            &mut self,
+            registry: Option<AccountId>,
+        ) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            self.registry = registry;
+            Ok(())
+        }
+
+        /// The arena configuration if the game reopens after it ended.
+        #[ink(message)]
+        pub fn arena(&self) -> Option<ArenaConfig> {
+            self.arena
+        }
+
+        /// Turn the game into an arena that reopens after it ended or back.
+        ///
+        /// Only the opener can do this and only before the game started.
+        #[ink(message)]
+        pub fn set_arena(&mut self, arena: Option<ArenaConfig>) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            if let Some(arena) = arena {
+                arena
+                    .season_share_bps
+                    .le(&MAX_BASIS_POINTS)
+                    .then_some(())
+                    .ok_or(GameError::InvalidShare)?;
+            }
+            self.arena = arena;
+            Ok(())
+        }
+
+        /// Which events are emitted while the game is played.
+        #[ink(message)]
+        pub fn event_mode(&self) -> EventMode {
+            self.event_mode
+        }
+
+        /// Change which events are emitted while the game is played.
+        ///
+        /// Only the opener can do this and only before the game started.
+        #[ink(message)]
+        pub fn set_event_mode(&mut self, mode: EventMode) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            self.event_mode = mode;
+            Ok(())
+        }
+
+        /// The account that is allowed to relay signed moves.
+        #[ink(message)]
+        pub fn relayer(&self) -> AccountId {
+            self.relayer
+        }
+
+        /// Change the account that is allowed to relay signed moves.
+        ///
+        /// Only the opener can do this and only before the game started.
+        #[ink(message)]
+        pub fn set_relayer(&mut self, relayer: AccountId) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            self.relayer = relayer;
+            Ok(())
+        }
+
+        /// Where the moves of the players come from.
+        #[ink(message)]
+        pub fn turn_mode(&self) -> TurnMode {
+            self.turn_mode
+        }
+
+        /// Change where the moves of the players come from.
+        ///
+        /// Only the opener can do this and only before the game started.
+        #[ink(message)]
+        pub fn set_turn_mode(&mut self, mode: TurnMode) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            self.turn_mode = mode;
+            Ok(())
+        }
+
+        /// The current game state.
+        #[ink(message)]
+        pub fn state(&self) -> State {
+            self.state.clone()
+        }
+
+        /// Returns `true` if the game is running.
+        #[ink(message)]
+        pub fn is_running(&self) -> bool {
+            if let State::Running { rounds_played, .. } = self.state {
+                rounds_played < self.rounds
+            } else {
+                false
+            }
+        }
+
+        /// List of all players sorted by score and gas costs.
+        #[ink(message)]
+        pub fn players_sorted(&self) -> Vec<Player> {
+            let mut players = self.players();
+            players.sort_unstable_by_key(|player| player.scoring_order());
+            players
+        }
+
+        /// Series points of all players, highest first.
+        ///
+        /// Points are collected over all games since the last [`reset_game`].
+        #[ink(message)]
+        pub fn series_standings(&self) -> Vec<(AccountId, u32)> {
+            let mut standings: Vec<_> = self
+                .players()
+                .into_iter()
+                .map(|player| (player.id, player.series_points))
+                .collect();
+            standings.sort_unstable_by_key(|(_, points)| Reverse(*points));
+            standings
+        }
+
+        /// The number of games played since the last [`reset_game`].
+        #[ink(message)]
+        pub fn series_games(&self) -> u32 {
+            self.series_games
+        }
+
+        /// Why the last failed turn of `player` failed.
+        ///
+        /// Returns `None` if the player never failed to make a turn.
+        #[ink(message)]
+        pub fn last_error(&self, player: AccountId) -> Option<TurnFailure> {
+            self.last_errors.get(player)
+        }
+
+        /// The number of fields that nobody painted yet.
+        #[ink(message)]
+        pub fn free_cells(&self) -> u32 {
+            self.dimensions.len().saturating_sub(self.claimed_cells)
+        }
+
+        /// The number of players that still have budget left to make turns.
+        #[ink(message)]
+        pub fn active_players(&self) -> u32 {
+            let players = self.players();
+            players
+                .iter()
+                .filter(|player| self.can_play(player, players.len()))
+                .count() as u32
+        }
+
+        /// Statistics about the turns of `player`.
+        #[ink(message)]
+        pub fn player_stats(&self, player: AccountId) -> Option<TurnStats> {
+            let players = self.players();
+            Self::find_player(&player, &players)
+                .ok()
+                .map(|idx| players[idx].stats)
+        }
+
+        /// Statistics about the turns of all players.
+        #[ink(message)]
+        pub fn all_player_stats(&self) -> Vec<(AccountId, TurnStats)> {
+            self.players()
+                .iter()
+                .map(|player| (player.id, player.stats))
+                .collect()
+        }
+
+        /// Snapshots of all rounds between `from_round` and `to_round` (inclusive).
+        ///
+        /// Only the most recent [`history_depth`] rounds are kept.
+        #[ink(message)]
+        pub fn score_history(
+            &self,
+            from_round: u32,
+            to_round: u32,
+        ) -> Vec<RoundSnapshot> {
+            if self.history_depth == 0 {
+                return Vec::new()
+            }
+            let from_round =
+                from_round.max(to_round.saturating_sub(self.history_depth - 1));
+            (from_round..=to_round)
+                .filter_map(|round| {
+                    self.history
+                        .get(round.rem_euclid(self.history_depth))
+                        .filter(|snapshot| snapshot.round == round)
+                })
+                .collect()
+        }
+
+        /// The number of rounds for which snapshots are kept.
+        #[ink(message)]
+        pub fn history_depth(&self) -> u32 {
+            self.history_depth
+        }
+
+        /// Change the number of rounds for which snapshots are kept. `0` disables the
+        /// history. At most [`HISTORY_DEPTH_LIMIT`] rounds can be kept.
+        ///
+        /// Only the opener can do this and only before the game started.
+        #[ink(message)]
+        pub fn set_history_depth(&mut self, depth: u32) -> Result<(), GameError> {
+            self.ensure_configurable()?;
+            (depth <= HISTORY_DEPTH_LIMIT)
+                .then_some(())
+                .ok_or(GameError::HistoryDepthIsTooLarge)?;
+            self.history_depth = depth;
+            Ok(())
+        }
+
+        /// The final standings of the current game if it is finished or else of the
+        /// previous one.
+        #[ink(message)]
+        pub fn result(&self) -> Option<GameResult> {
+            self.last_result.get()
+        }
+
+        /// The commitment to all fields painted up to and including `round`.
+        ///
+        /// It is the blake2-256 hash of the SCALE encoded `(previous, round, moves)`
+        /// where `previous` is the commitment of the round before (zero for the first
+        /// round) and `moves` are the `(player, field)` pairs of all `Success`ful
+        /// turns of the round in the order they were made.
+        #[ink(message)]
+        pub fn round_commitment(&self, round: u32) -> Option<Hash> {
+            self.commitments.get(round)
+        }
+
+        /// Returns the dimensions of the board.
+        #[ink(message)]
+        pub fn dimensions(&self) -> Field {
+            self.dimensions
+        }
+
+        /// Returns the value (owner) of the supplied field.
+        #[ink(message)]
+        pub fn field(&self, coord: Field) -> Option<FieldEntry> {
+            self.idx(&coord).and_then(|idx| self.board.get(idx))
+        }
+
+        /// Returns the complete board.
+        ///
+        /// The index into the vector is calculated as `x + y * width`.
+        #[ink(message)]
+        pub fn board(&self) -> Vec<Option<FieldEntry>> {
+            self.board_iter().collect()
+        }
+
+        fn calc_gas_limit(num_players: usize) -> u64 {
+            (GAS_LIMIT_ALL_PLAYERS
+                .saturating_mul(u64::from(Self::calc_num_batches(num_players))))
+                .checked_div(num_players as u64)
+                .unwrap_or(0)
+        }
+
+        /// Let `player` make its turn and apply it to the board.
+        ///
+        /// Returns the outcome together with the gas the player was charged for it.
+        ///
+        /// The move is taken from `relayed` if it was signed off-chain.
+        fn play_turn(
+            &mut self,
+            player: &mut Player,
+            game_info: &mut GameInfo,
+            num_players: usize,
+            relayed: Option<Field>,
+        ) -> (TurnOutcome, u64) {
+            // Stop calling a contract that has no gas left.
+            let gas_limit = Self::calc_gas_limit(num_players);
+            let gas_left = self.gas_left(player, num_players);
+            if gas_left == 0 {
+                return (TurnOutcome::BudgetExhausted, 0)
+            }
+            if game_info.rounds_played < player.frozen_until {
+                return (TurnOutcome::Frozen, 0)
+            }
+            game_info.gas_left = gas_left;
+
+            // Moves that weren't computed on-chain are charged on a fixed schedule.
+            let (turn, gas_used) = match (relayed, player.kind) {
+                (Some(turn), _) => {
+                    (Ok(Ok(Some(turn))), GAS_PER_QUEUED_MOVE.min(gas_left))
+                }
+                (None, PlayerKind::Human) => {
+                    let turn = self.pop_queued_move(&player.id);
+                    (Ok(Ok(turn)), GAS_PER_QUEUED_MOVE.min(gas_left))
+                }
+                (None, PlayerKind::Contract) => {
+                    // The runtime would reject calling back into the contract that
+                    // cranked the game. It sits the round out instead of failing the
+                    // whole round and isn't punished for keeping the game going.
+                    if Self::env().caller() == player.id {
+                        return (TurnOutcome::Cranked, 0)
+                    }
+                    Self::invoke_player(player.id, game_info, gas_limit)
+                }
+            };
+            // We continue even if the contract call fails. If the contract
+            // doesn't conform it is the players fault. No second tries.
+            let outcome = match turn {
+                Ok(Ok(Some(turn))) => {
+                    // Player tried to make a turn: charge gas.
+                    player.gas_used = player.gas_used.saturating_add(gas_used);
+                    let idx = self.idx(&turn).filter(|_| self.is_valid_coord(&turn));
+                    if let Some(entry) = idx.and_then(|idx| self.board.get(idx)) {
+                        TurnOutcome::Occupied {
+                            turn,
+                            player: entry.owner,
+                        }
+                    } else if let Some(idx) = idx {
+                        self.board.insert(
+                            idx,
+                            &FieldEntry {
+                                owner: player.id,
+                                claimed_at: game_info.rounds_played,
+                            },
+                        );
+                        player.score = player.score.saturating_add(u64::from(
+                            game_info.rounds_played.saturating_add(1),
+                        ));
+                        self.claimed_cells = self.claimed_cells.saturating_add(1);
+                        TurnOutcome::Success { turn }
+                    } else {
+                        TurnOutcome::OutOfBounds { turn }
+                    }
+                }
+                Ok(Ok(None)) => return (TurnOutcome::NoTurn, 0),
+                err => {
+                    // Player gets charged gas for failing.
+                    player.gas_used = player.gas_used.saturating_add(gas_used);
+                    debug_println!("Contract failed to make a turn: {:?}", err);
+                    let reason =
+                        Self::turn_failure(&player.id, err, gas_used >= gas_limit);
+                    self.last_errors.insert(player.id, &reason);
+                    TurnOutcome::BrokenPlayer { reason }
+                }
+            };
+
+            (outcome, gas_used)
+        }
+
+        /// Advance the game by one round.
+        ///
+        /// Returns the round that is to be played now.
+        fn begin_round(&mut self) -> Result<u32, GameError> {
+            self.is_running()
+                .then_some(())
+                .ok_or(GameError::GameCannotBeEndedOrHasAlreadyEnded)?;
+
+            let State::Running { rounds_played } = &mut self.state else {
+                return Err(GameError::ThisGameDoesNotAcceptTurnsRightNow);
+            };
+
+            // Only one turn per block
+            // We need to write this to storage because of reentrancy: The called contract
+            // could call this function again and do another turn in the same block.
+            let current_block = Self::env().block_number();
+            let last_turn = self
+                .last_turn
+                .get()
+                .ok_or(GameError::ValueWasNotSetWhenStartingTheGame)?;
+
+            last_turn
+                .lt(&current_block)
+                .then_some(())
+                .ok_or(GameError::TurnWasAlreadySubmittedForThisBlock)?;
+
+            self.last_turn.set(&current_block);
+            self.in_round.set(&true);
+
+            let current_round = *rounds_played;
+            *rounds_played = rounds_played.saturating_add(1);
+            Ok(current_round)
+        }
+
+        /// Apply a penalty to `player` and record it in the audit log.
+        fn penalize(
+            &mut self,
+            player: AccountId,
+            reason: String,
+            apply: impl FnOnce(&mut Self, &mut Player) -> PenaltyAction,
+        ) -> Result<(), GameError> {
+            self.ensure_not_in_round()?;
+            (Self::env().caller() == self.referee)
+                .then_some(())
+                .ok_or(GameError::OnlyRefereeCanPenalizePlayers)?;
+            let res = !matches!(self.state, State::Finished { .. });
+            res.then_some(())
+                .ok_or(GameError::PlayersCanOnlyBePenalizedBeforeTheGameIsFinished)?;
+            ALLOWED_REASON_SIZES
+                .contains(&reason.len())
+                .then_some(())
+                .ok_or(GameError::InvalidLengthForReason)?;
+
+            let mut players = self.players();
+            let idx = Self::find_player(&player, &players)
+                .map_err(|_| GameError::PlayerIsNotRegistered)?;
+            let action = apply(self, &mut players[idx]);
+            self.players.set(&players);
+
+            Self::env().emit_event(PlayerPenalized {
+                player,
+                action,
+                reason: reason.clone(),
+            });
+            self.audit_log.insert(
+                self.audit_log_len,
+                &Penalty {
+                    player,
+                    action,
+                    reason,
+                    round: self.rounds_played(),
+                },
+            );
+            self.audit_log_len = self.audit_log_len.saturating_add(1);
+            Ok(())
+        }
+
+        /// The number of rounds played in the current game.
+        fn rounds_played(&self) -> u32 {
+            match self.state {
+                State::Running { rounds_played }
+                | State::Ended { rounds_played, .. } => rounds_played,
+                _ => 0,
+            }
+        }
+
+        /// Record the outcome of a turn.
+        ///
+        /// The turn is added to the `log` of the round. Players that break too many
+        /// turns in a row are disqualified.
+        fn finish_turn(
+            &mut self,
+            player: &mut Player,
+            idx: usize,
+            outcome: TurnOutcome,
+            gas_charged: u64,
+            log: &mut RoundLog,
+        ) {
+            player.stats.record(&outcome, gas_charged);
+            if let TurnOutcome::Success { turn } = outcome {
+                log.applied.push((player.id, turn));
+            }
+            match outcome {
+                TurnOutcome::BrokenPlayer { .. } | TurnOutcome::OutOfBounds { .. } => {
+                    player.strikes = player.strikes.saturating_add(1);
+                }
+                TurnOutcome::BudgetExhausted
+                | TurnOutcome::Frozen
+                | TurnOutcome::Cranked => (),
+                _ => player.strikes = 0,
+            }
+            match self.event_mode {
+                EventMode::PerTurn => {
+                    Self::env().emit_event(TurnTaken {
+                        player: player.id,
+                        outcome,
+                    });
+                }
+                EventMode::RoundSummary => {
+                    log.turns.push(TurnSummary {
+                        player: idx as u8,
+                        outcome: outcome.kind(),
+                        turn: outcome.turn(),
+                    });
+                }
+            }
+
+            if self.strike_limit.is_some_and(|limit| player.strikes >= limit) {
+                player.status = PlayerStatus::Disqualified;
+                let fields_released = self.release_fields_on_disqualification;
+                if fields_released {
+                    self.release_fields(player);
+                }
+                Self::env().emit_event(PlayerDisqualified {
+                    player: player.id,
+                    fields_released,
+                });
+            }
+        }
+
+        /// Finish the round and conclude the game early if playing on is pointless.
+        ///
+        /// The fields painted in this round are committed to together with the
+        /// commitment of the previous round.
+        fn end_round(&mut self, current_round: u32, players: &Vec<Player>, log: RoundLog) {
+            self.in_round.set(&false);
+            let rounds_played = current_round.saturating_add(1);
+            match self.event_mode {
+                EventMode::PerTurn => {
+                    Self::env().emit_event(RoundIncremented { rounds_played });
+                }
+                EventMode::RoundSummary => {
+                    Self::env().emit_event(RoundSummary {
+                        rounds_played,
+                        turns: log.turns,
+                    });
+                }
+            }
+            self.players.set(players);
+
+            let previous = current_round
+                .checked_sub(1)
+                .and_then(|round| self.commitments.get(round))
+                .unwrap_or_default();
+            let commitment = Self::env().hash_encoded::<Blake2x256, _>(&(
+                previous,
+                current_round,
+                log.applied,
+            ));
+            self.commitments.insert(current_round, &Hash::from(commitment));
+
+            if self.history_depth > 0 {
+                let snapshot = RoundSnapshot {
+                    round: current_round,
+                    players: players
+                        .iter()
+                        .map(|player| {
+                            PlayerSnapshot {
+                                id: player.id,
+                                score: player.score,
+                                gas_used: player.gas_used,
+                            }
+                        })
+                        .collect(),
+                };
+                self.history
+                    .insert(current_round.rem_euclid(self.history_depth), &snapshot);
+            }
+
+            let num_players = players.len();
+            let reason = if self.free_cells() == 0 {
+                ConclusionReason::BoardFull
+            } else if !players.iter().any(Player::is_active) {
+                ConclusionReason::NoActivePlayers
+            } else if !players
+                .iter()
+                .any(|player| self.can_play(player, num_players))
+            {
+                ConclusionReason::AllBudgetsExhausted
+            } else {
+                return
+            };
+            if rounds_played < self.rounds {
+                self.state = State::Ended {
+                    rounds_played,
+                    reason,
+                };
+                Self::env().emit_event(GameConcluded { reason });
+            }
+        }
+
+        /// Whether the player is active and has budget left to make turns.
+        fn can_play(&self, player: &Player, num_players: usize) -> bool {
+            player.is_active() && self.gas_left(player, num_players) > 0
+        }
+
+        /// Remove all fields painted by `player` from the board.
+        ///
+        /// The score the player got for those fields is taken away.
+        fn release_fields(&mut self, player: &mut Player) {
+            let to = Field {
+                x: self.dimensions.x.saturating_sub(1),
+                y: self.dimensions.y.saturating_sub(1),
+            };
+            self.release_fields_in(player, Field { x: 0, y: 0 }, to);
+        }
+
+        /// Remove the fields painted by `player` inside the region spanned by `from`
+        /// and `to` (inclusive) from the board.
+        ///
+        /// The score the player got for those fields is taken away. Returns the
+        /// number of released fields.
+        fn release_fields_in(
+            &mut self,
+            player: &mut Player,
+            from: Field,
+            to: Field,
+        ) -> u32 {
+            let mut released = 0;
+            for y in from.y..=to.y.min(self.dimensions.y.saturating_sub(1)) {
+                for x in from.x..=to.x.min(self.dimensions.x.saturating_sub(1)) {
+                    let Some(idx) = self.idx(&Field { x, y }) else {
+                        continue;
+                    };
+                    let Some(entry) = self.board.get(idx) else {
+                        continue;
+                    };
+                    if entry.owner != player.id {
+                        continue;
+                    }
+                    self.board.remove(idx);
+                    self.claimed_cells = self.claimed_cells.saturating_sub(1);
+                    player.stats.cells_owned = player.stats.cells_owned.saturating_sub(1);
+                    player.score = player
+                        .score
+                        .saturating_sub(u64::from(entry.claimed_at.saturating_add(1)));
+                    released += 1;
+                }
+            }
+            released
+        }
+
+        /// The gas a player has left for the rest of the game.
+        fn gas_left(&self, player: &Player, num_players: usize) -> u64 {
+            let gas_limit = Self::calc_gas_limit(num_players);
+            Self::calc_gas_budget(gas_limit, self.rounds).saturating_sub(player.gas_used)
+        }
+
+        /// Whether `signed` was signed by `owner` for the current game and `round`.
+        ///
+        /// The account ID of an ECDSA key is the blake2-256 hash of its compressed
+        /// public key.
+        fn verify_signed_move(
+            &self,
+            signed: &SignedMove,
+            owner: &AccountId,
+            round: u32,
+        ) -> bool {
+            let message_hash = Self::signed_move_hash(
+                Self::env().account_id(),
+                self.games_started,
+                round,
+                signed.turn,
+            );
+            let Ok(public_key) =
+                Self::env().ecdsa_recover(&signed.signature, &message_hash)
+            else {
+                return false
+            };
+            let signer = Self::env().hash_bytes::<Blake2x256>(&public_key);
+            AccountId::from(signer) == *owner
+        }
+
+        /// The hash the owner of a player signs to make `turn` in `round`.
+        fn signed_move_hash(
+            game: AccountId,
+            games_started: u32,
+            round: u32,
+            turn: Field,
+        ) -> [u8; 32] {
+            Self::env().hash_encoded::<Blake2x256, _>(&(game, games_started, round, turn))
+        }
+
+        /// Take the oldest move from the queue of a human player.
+        fn pop_queued_move(&mut self, id: &AccountId) -> Option<Field> {
+            let mut queue = self.move_queues.get(id)?;
+            let turn = (!queue.is_empty()).then(|| queue.remove(0));
+            if queue.is_empty() {
+                self.move_queues.remove(id);
+            } else {
+                self.move_queues.insert(id, &queue);
+            }
+            turn
+        }
+
+        /// Call the selector-0 message of a player contract.
+        ///
+        /// Returns the result of the call together with the gas it consumed.
+        fn invoke_player(
+            id: AccountId,
+            game_info: &GameInfo,
+            gas_limit: u64,
+        ) -> (Result<MessageResult<Option<Field>>, EnvError>, u64) {
+            // We need to call with reentrancy enabled to allow those
+            // contracts to query us.
+            let call = build_call::<DefaultEnvironment>()
+                .call_type(Call::new(id))
+                .gas_limit(gas_limit)
+                .exec_input(
+                    ExecutionInput::new(Selector::from([0x00; 4])).push_arg(game_info),
+                )
+                .call_flags(CallFlags::default().set_allow_reentry(true))
+                .returns::<Option<Field>>();
+
+            let gas_before = Self::env().gas_left();
+            let result = call.try_invoke();
+            (result, gas_before.saturating_sub(Self::env().gas_left()))
+        }
+
+        /// Make sure `id` passes the configured [`RegistrationCheck`].
+        fn check_registration(&self, id: AccountId) -> Result<(), GameError> {
+            match self.registration_check {
+                RegistrationCheck::Disabled => Ok(()),
+                RegistrationCheck::CodeHashAllowlist => {
+                    Self::env()
+                        .code_hash(&id)
+                        .ok()
+                        .filter(|code_hash| self.allowed_code_hashes.contains(code_hash))
+                        .map(|_| ())
+                        .ok_or(GameError::CodeHashIsNotAllowed)
+                }
+                RegistrationCheck::DryCall => {
+                    // The check runs in a sub-call so that all of its state changes
+                    // can be reverted.
+                    let result = build_call::<DefaultEnvironment>()
+                        .call_type(Call::new(Self::env().account_id()))
+                        .exec_input(
+                            ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                                "conformance_check"
+                            )))
+                            .push_arg(id),
+                        )
+                        .call_flags(CallFlags::default().set_allow_reentry(true))
+                        .returns::<Result<(), GameError>>()
+                        .try_invoke();
+                    match result {
+                        Ok(Ok(res)) => res,
+                        _ => Err(GameError::PlayerFailedConformanceCheck),
+                    }
+                }
+            }
+        }
+
+        /// Return `value` from the current message but revert all state changes.
+        fn revert<R: scale::Encode>(value: &R) -> ! {
+            ink::env::return_value::<MessageResult<&R>>(
+                ReturnFlags::new_with_reverted(true),
+                &Ok(value),
+            )
+        }
+
+        /// Classify why a player call didn't produce a turn.
+        fn turn_failure(
+            id: &AccountId,
+            result: Result<MessageResult<Option<Field>>, EnvError>,
+            gas_exhausted: bool,
+        ) -> TurnFailure {
+            if !Self::env().is_contract(id) {
+                return TurnFailure::NotAContract
+            }
+            match result {
+                Ok(Err(error)) => TurnFailure::DecodingFailed { error: Some(error) },
+                Err(EnvError::Decode(_)) => TurnFailure::DecodingFailed { error: None },
+                Err(EnvError::CodeNotFound | EnvError::NotCallable) => {
+                    TurnFailure::NotAContract
+                }
+                _ if gas_exhausted => TurnFailure::OutOfGas,
+                _ => TurnFailure::Trapped,
+            }
+        }
+
+        /// Bring a finished game back into the forming phase.
+        ///
+        /// Clears the board and everything recorded while playing but leaves the
+        /// players alone.
+        fn clear_game(&mut self) -> Result<(), GameError> {
+            matches!(self.state, State::Finished { .. })
+                .then_some(())
+                .ok_or(GameError::OnlyFinishedGameCanBeReset)?;
+            // The result of the last game is kept around.
+            let rounds_played = self
+                .last_result
+                .get()
+                .map_or(0, |result| result.rounds_played);
+            for round in 0..rounds_played {
+                self.commitments.remove(round);
+            }
+            self.state = State::Forming {
+                earliest_start: Self::env().block_number(),
+            };
+            for x in 0..self.dimensions.x {
+                for y in 0..self.dimensions.y {
+                    self.board.remove(self.idx(&Field { x, y }).unwrap());
+                }
+            }
+            for player in self.players() {
+                self.last_errors.remove(player.id);
+                self.move_queues.remove(player.id);
+            }
+            // Slots of rounds that were never played are empty anyway.
+            for slot in 0..self.history_depth.min(rounds_played) {
+                self.history.remove(slot);
+            }
+            self.sponsors.set(&Vec::new());
+            self.pot = self.pot.saturating_add(core::mem::take(&mut self.rollover));
+            self.last_turn.set(&0);
+            self.claimed_cells = 0;
+            Ok(())
+        }
+
+        /// Bring the finished arena game back into the forming phase.
+        ///
+        /// Players with enough credit pay their buy-in from it and stay registered.
+        /// All others are removed and get their remaining credit back.
+        fn reopen_arena(&mut self, cooldown: u32) -> Result<(), GameError> {
+            self.clear_game()?;
+            let earliest_start = Self::env().block_number().saturating_add(cooldown);
+            self.state = State::Forming { earliest_start };
+
+            let mut staying = Vec::new();
+            for mut player in self.players() {
+                let credit = self.reentry_credit.get(player.id);
+                match credit {
+                    Some(credit) if credit >= self.buy_in => {
+                        self.reentry_credit
+                            .insert(player.id, &credit.saturating_sub(self.buy_in));
+                        self.reentry_total =
+                            self.reentry_total.saturating_sub(self.buy_in);
+                        player.prepare_rematch();
+                        player.status = PlayerStatus::Active;
+                        self.pot = self.pot.saturating_add(self.buy_in);
+                        staying.push(player);
+                    }
+                    Some(credit) => {
+                        self.reentry_credit.remove(player.id);
+                        self.reentry_total = self.reentry_total.saturating_sub(credit);
+                        if credit > 0 {
+                            self.owe(player.owner, credit);
+                        }
+                    }
+                    None => (),
+                }
+            }
+            self.players.set(&staying);
+            Self::env().emit_event(ArenaReopened {
+                earliest_start,
+                players: staying.len() as u32,
+            });
+            Ok(())
+        }
+
+        /// Pay the keeper fee to the caller for advancing the round.
+        ///
+        /// Nothing is paid if the fund is empty or the caller was already paid for
+        /// the maximum number of rounds in this game. A failed transfer doesn't fail
+        /// the round.
+        fn pay_keeper(&mut self) {
+            let Some(keeper) = self.keeper else {
+                return
+            };
+            if self.keeper_fund < keeper.fee {
+                return
+            }
+            let caller = Self::env().caller();
+            let paid = match self.keeper_rounds.get(caller) {
+                Some((game, paid)) if game == self.games_started => paid,
+                _ => 0,
+            };
+            if paid >= keeper.max_rounds_per_keeper {
+                return
+            }
+            if self.pay(caller, keeper.fee).is_ok() {
+                self.keeper_fund = self.keeper_fund.saturating_sub(keeper.fee);
+                self.keeper_rounds
+                    .insert(caller, &(self.games_started, paid.saturating_add(1)));
+                Self::env().emit_event(KeeperPaid {
+                    keeper: caller,
+                    amount: keeper.fee,
+                });
+            }
+        }
+
+        /// Mint a trophy to the owner of every top player.
+        ///
+        /// Disqualified players don't get a trophy. A failed mint doesn't stop the
+        /// game from ending.
+        fn mint_trophies(&self, trophies: TrophyConfig, result: &GameResult) {
+            let winners = (1..)
+                .zip(result.standings.iter())
+                .take(trophies.count as usize)
+                .filter(|(_, standing)| standing.status != PlayerStatus::Disqualified);
+            for (rank, standing) in winners {
+                let trophy = Trophy {
+                    game: Self::env().account_id(),
+                    rank,
+                    score: standing.score,
+                    gas_used: standing.gas_used,
+                    board_hash: result.board_hash,
+                };
+                let minted = build_call::<DefaultEnvironment>()
+                    .call_type(Call::new(trophies.collection))
+                    .gas_limit(TROPHY_MINT_GAS_LIMIT)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                            "Trophies::mint"
+                        )))
+                        .push_arg(standing.owner)
+                        .push_arg(trophy),
+                    )
+                    .returns::<Result<(), PSP34Error>>()
+                    .try_invoke();
+                if let Ok(Ok(Ok(()))) = minted {
+                    Self::env().emit_event(TrophyMinted {
+                        owner: standing.owner,
+                        player: standing.id,
+                        rank,
+                    });
+                }
+            }
+        }
+
+        /// Report the owners of all players ordered by their final rank to `ratings`.
+        ///
+        /// A failed report doesn't stop the game from ending.
+        fn report_result(ratings: AccountId, result: &GameResult) {
+            let owners: Vec<AccountId> = result
+                .standings
+                .iter()
+                .map(|standing| standing.owner)
+                .collect();
+            let _ = build_call::<DefaultEnvironment>()
+                .call_type(Call::new(ratings))
+                .gas_limit(RATINGS_REPORT_GAS_LIMIT)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("report")))
+                        .push_arg(owners),
+                )
+                .returns::<Result<(), RatingsError>>()
+                .try_invoke();
+        }
+
+        /// Give `player` `points` more season points if the season has room for it.
+        fn add_season_points(
+            &mut self,
+            player: AccountId,
+            owner: AccountId,
+            points: u32,
+        ) {
+            let current = match self.season_points.get(player) {
+                Some((_, current)) => current,
+                None if self.season_len < SEASON_LIMIT => {
+                    self.season_players.insert(self.season_len, &player);
+                    self.season_len += 1;
+                    0
+                }
+                None => return,
+            };
+            self.season_points
+                .insert(player, &(owner, current.saturating_add(points)));
+        }
+
+        /// The name `owner` reserved in the configured registry.
+        fn identity_name(&self, owner: AccountId) -> Result<String, GameError> {
+            let registry = self.registry.ok_or(GameError::RegistryIsNotConfigured)?;
+            let identity = build_call::<DefaultEnvironment>()
+                .call_type(Call::new(registry))
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("identity")))
+                        .push_arg(owner),
+                )
+                .returns::<Option<Identity>>()
+                .try_invoke()?
+                .map_err(|_| GameError::RegistryCallFailed)?;
+            identity
+                .map(|identity| identity.name)
+                .ok_or(GameError::NoIdentityInRegistry)
+        }
+
+        /// The owner that reserved `name` in `registry`.
+        fn registry_owner_of(
+            registry: AccountId,
+            name: &str,
+        ) -> Result<Option<AccountId>, GameError> {
+            build_call::<DefaultEnvironment>()
+                .call_type(Call::new(registry))
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("owner_of")))
+                        .push_arg(name),
+                )
+                .returns::<Option<AccountId>>()
+                .try_invoke()?
+                .map_err(|_| GameError::RegistryCallFailed)
+        }
+
+        /// Take the buy-in from the caller.
+        ///
+        /// Native buy-ins have to be transferred with the call. Token buy-ins are
+        /// pulled from the caller and need to be approved beforehand.
+        fn collect_buy_in(&self) -> Result<(), GameError> {
+            self.collect(self.buy_in)?
+                .eq(&self.buy_in)
+                .then_some(())
+                .ok_or(GameError::WrongBuyIn)
+        }
+
+        /// The amount the caller pays with the current call.
+        ///
+        /// Without a buy-in token this is the transferred value and `amount` is
+        /// ignored. Otherwise `amount` is pulled from the caller.
+        fn collect(&self, amount: Balance) -> Result<Balance, GameError> {
+            let Some(token) = self.buy_in_token else {
+                return Ok(Self::env().transferred_value())
+            };
+            self.ensure_no_native_value()?;
+            if amount == 0 {
+                return Ok(0)
+            }
+            build_call::<DefaultEnvironment>()
+                .call_type(Call::new(token))
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                        "PSP22::transfer_from"
+                    )))
+                    .push_arg(Self::env().caller())
+                    .push_arg(Self::env().account_id())
+                    .push_arg(amount)
+                    .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()?
+                .map_err(|_| GameError::TokenCallFailed)?
+                .map_err(GameError::TokenTransferFailed)?;
+            Ok(amount)
+        }
+
+        /// Transfer `amount` of the currency the game is paid in to `to`.
+        fn pay(&self, to: AccountId, amount: Balance) -> Result<(), GameError> {
+            let Some(token) = self.buy_in_token else {
+                return Ok(Self::env().transfer(to, amount)?)
+            };
+            build_call::<DefaultEnvironment>()
+                .call_type(Call::new(token))
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                        "PSP22::transfer"
+                    )))
+                    .push_arg(to)
+                    .push_arg(amount)
+                    .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()?
+                .map_err(|_| GameError::TokenCallFailed)?
+                .map_err(GameError::TokenTransferFailed)
+        }
+
+        /// The balance the game holds in the currency it is paid in.
+        ///
+        /// For the native currency this excludes the existential deposit.
+        fn free_balance(&self) -> Result<Balance, GameError> {
+            let Some(token) = self.buy_in_token else {
+                return Ok(Self::env()
+                    .balance()
+                    .saturating_sub(Self::env().minimum_balance()))
+            };
+            build_call::<DefaultEnvironment>()
+                .call_type(Call::new(token))
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                        "PSP22::balance_of"
+                    )))
+                    .push_arg(Self::env().account_id()),
+                )
+                .returns::<Balance>()
+                .try_invoke()?
+                .map_err(|_| GameError::TokenCallFailed)
+        }
+
+        /// Reject messages from players calling back into the game during their turn.
+        ///
+        /// The round writes back the state it loaded before calling the players, so
+        /// anything they change in between would be lost.
+        fn ensure_not_in_round(&self) -> Result<(), GameError> {
+            (!self.in_round.get().unwrap_or_default())
+                .then_some(())
+                .ok_or(GameError::NotAllowedWhileARoundIsPlayed)
+        }
+
+        /// Everything the game owes to someone.
+        fn reserved(&self) -> Balance {
+            self.pot
+                .saturating_add(self.season_pot)
+                .saturating_add(self.keeper_fund)
+                .saturating_add(self.reentry_total)
+                .saturating_add(self.prizes_total)
+                .saturating_add(self.rollover)
+        }
+
+        /// Native value can only be deposited if the game is paid in the native
+        /// currency.
+        fn ensure_no_native_value(&self) -> Result<(), GameError> {
+            (self.buy_in_token.is_none() || Self::env().transferred_value() == 0)
+                .then_some(())
+                .ok_or(GameError::NativeValueIsNotAccepted)
+        }
+
+        /// Record that `amount` is owed to `account`.
+        fn owe(&mut self, account: AccountId, amount: Balance) {
+            let owed = self.prizes.get(account).unwrap_or_default();
+            self.prizes.insert(account, &owed.saturating_add(amount));
+            self.prizes_total = self.prizes_total.saturating_add(amount);
+        }
+
+        /// `share` basis points of `amount`.
+        fn share_of(amount: Balance, share: u16) -> Balance {
+            amount.saturating_mul(Balance::from(share)) / Balance::from(MAX_BASIS_POINTS)
+        }
+
+        fn calc_num_batches(num_players: usize) -> u32 {
+            if num_players > 30 {
+                2
+            } else {
+                1
+            }
+        }
+
+        fn calc_gas_budget(gas_limit: u64, num_rounds: u32) -> u64 {
+            gas_limit.saturating_mul(u64::from(num_rounds).saturating_div(4))
+        }
+
+        /// Configuration can only be changed by the opener while the game is forming.
+        fn ensure_configurable(&self) -> Result<(), GameError> {
+            (Self::env().caller() == self.opener)
+                .then_some(())
+                .ok_or(GameError::OnlyAdminCanConfigureTheGame)?;
+            matches!(self.state, State::Forming { .. })
+                .then_some(())
+                .ok_or(GameError::GameCanOnlyBeConfiguredInTheFormingPhase)
+        }
+
+        fn players(&self) -> Vec<Player> {
+            self.players
+                .get()
+                .expect("Initial value is set in constructor.")
+        }
+
+        /// Hash of the complete board.
+        ///
+        /// The board is hashed row by row so that large boards don't need to be
+        /// encoded at once.
+        fn board_hash(&self) -> Hash {
+            (0..self.dimensions.y)
+                .fold(Hash::default(), |hash, y| {
+                    let row: Vec<_> = (0..self.dimensions.x)
+                        .map(|x| self.field(Field { x, y }))
+                        .collect();
+                    Hash::from(Self::env().hash_encoded::<Blake2x256, _>(&(hash, row)))
+                })
+        }
+
+        fn board_iter(&self) -> impl Iterator<Item=Option<FieldEntry>> + '_ {
+            (0..self.dimensions.y).flat_map(move |y| {
+                (0..self.dimensions.x).map(move |x| self.field(Field { x, y }))
+            })
+        }
+
+        fn find_player(id: &AccountId, players: &[Player]) -> Result<usize, usize> {
+            players.binary_search_by_key(id, |player| player.id)
+        }
+
+        fn idx(&self, coord: &Field) -> Option<u32> {
+            coord
+                .y
+                .checked_mul(self.dimensions.x)
+                .and_then(|val| val.checked_add(coord.x))
+        }
+
+        fn is_valid_coord(&self, coord: &Field) -> bool {
+            self.idx(coord)
+                .map(|val| val < self.dimensions.len())
+                .unwrap_or(false)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{
+            advance_block,
+            default_accounts,
+            set_caller,
+            set_value_transferred,
+        };
+        use secp256k1::{
+            Message,
+            PublicKey,
+            SecretKey,
+            SECP256K1,
+        };
+
+        const TURN: Field = Field { x: 1, y: 2 };
+
+        #[ink::test]
+        fn signed_move_is_played() {
+            let (key, owner) = signer(1);
+            let mut game = game_with_signed_moves(owner, |_| ());
+
+            game.submit_signed_turns(vec![signed_move(&key, owner, 1, TURN)])
+                .unwrap();
+
+            assert_eq!(game.field(TURN).map(|entry| entry.owner), Some(owner));
+        }
+
+        #[ink::test]
+        fn only_relayer_can_submit_signed_turns() {
+            let (key, owner) = signer(1);
+            let mut game = game_with_signed_moves(owner, |_| ());
+
+            let bob = default_accounts::<DefaultEnvironment>().bob;
+            set_caller::<DefaultEnvironment>(bob);
+            assert_eq!(
+                game.submit_signed_turns(vec![signed_move(&key, owner, 1, TURN)]),
+                Err(GameError::OnlyRelayerCanSubmitSignedTurns)
+            );
+        }
+
+        #[ink::test]
+        fn move_signed_by_someone_else_is_skipped() {
+            let (_, owner) = signer(1);
+            let (other, _) = signer(2);
+            let mut game = game_with_signed_moves(owner, |_| ());
+
+            game.submit_signed_turns(vec![signed_move(&other, owner, 1, TURN)])
+                .unwrap();
+
+            assert!(game.field(TURN).is_none());
+            assert_eq!(game.rounds_played(), 1);
+        }
+
+        #[ink::test]
+        fn move_signed_for_another_game_is_skipped() {
+            let (key, owner) = signer(1);
+            let mut game = game_with_signed_moves(owner, |_| ());
+
+            game.submit_signed_turns(vec![signed_move(&key, owner, 0, TURN)])
+                .unwrap();
+
+            assert!(game.field(TURN).is_none());
+        }
+
+        #[ink::test]
+        fn only_first_move_of_a_player_is_played() {
+            let (key, owner) = signer(1);
+            let mut game = game_with_signed_moves(owner, |_| ());
+            let other_turn = Field { x: 0, y: 0 };
+
+            game.submit_signed_turns(vec![
+                signed_move(&key, owner, 1, TURN),
+                signed_move(&key, owner, 1, other_turn),
+            ])
+            .unwrap();
+
+            assert!(game.field(TURN).is_some());
+            assert!(game.field(other_turn).is_none());
+        }
+
+        #[ink::test]
+        fn history_keeps_only_the_most_recent_rounds() {
+            let mut game = running_game(0, |game| game.set_history_depth(2).unwrap());
+            play_rounds(&mut game, 4);
+
+            let rounds: Vec<u32> = game
+                .score_history(0, 3)
+                .iter()
+                .map(|snapshot| snapshot.round)
+                .collect();
+            assert_eq!(rounds, [2, 3]);
+        }
+
+        #[ink::test]
+        fn history_depth_is_limited() {
+            let mut game = SquinkSplash::new(Field { x: 4, y: 4 }, 0, 0, 4);
+            assert_eq!(
+                game.set_history_depth(HISTORY_DEPTH_LIMIT + 1),
+                Err(GameError::HistoryDepthIsTooLarge)
+            );
+            assert_eq!(game.set_history_depth(HISTORY_DEPTH_LIMIT), Ok(()));
+        }
+
+        #[ink::test]
+        fn history_is_cleared_for_the_next_game() {
+            let mut game = running_game(0, |game| game.set_history_depth(2).unwrap());
+            play_rounds(&mut game, 4);
+            game.end_game().unwrap();
+            game.reset_game().unwrap();
+
+            assert!(game.score_history(0, 3).is_empty());
+        }
+
+        #[ink::test]
+        fn season_pot_is_owed_to_the_owner() {
+            let accounts = default_accounts::<DefaultEnvironment>();
+            let mut game = running_game(100, |game| {
+                game.set_arena(Some(ArenaConfig {
+                    cooldown: 0,
+                    season_share_bps: 5_000,
+                }))
+                .unwrap()
+            });
+            play_rounds(&mut game, 4);
+            game.end_game().unwrap();
+            assert_eq!(game.season_pot(), 50);
+            assert_eq!(game.unclaimed_prize(accounts.bob), 50);
+
+            game.end_season().unwrap();
+
+            assert_eq!(game.season_pot(), 0);
+            assert_eq!(game.unclaimed_prize(accounts.bob), 100);
+            assert_eq!(game.unclaimed_prize(accounts.charlie), 0);
+            assert!(game.season_standings().is_empty());
+        }
+
+        #[ink::test]
+        fn strike_limit_of_zero_is_rejected() {
+            let mut game = SquinkSplash::new(Field { x: 4, y: 4 }, 0, 0, 4);
+            assert_eq!(
+                game.set_strike_limit(Some(0), false),
+                Err(GameError::StrikeLimitMustBeAtLeastOne)
+            );
+        }
+
+        #[ink::test]
+        fn opener_destroys_game_in_which_everyone_was_disqualified() {
+            let (key, owner) = signer(1);
+            let mut game = game_with_signed_moves(owner, |game| {
+                game.set_strike_limit(Some(1), false).unwrap()
+            });
+            let out_of_bounds = Field { x: 9, y: 9 };
+            game.submit_signed_turns(vec![signed_move(&key, owner, 1, out_of_bounds)])
+                .unwrap();
+            game.end_game().unwrap();
+
+            let alice = default_accounts::<DefaultEnvironment>().alice;
+            let State::Finished { winner } = game.state() else {
+                panic!("the game should be finished");
+            };
+            assert_eq!(winner, alice);
+            let balance = ink::env::balance::<DefaultEnvironment>();
+            ink::env::test::assert_contract_termination::<DefaultEnvironment, _>(
+                move || game.destroy().unwrap(),
+                alice,
+                balance,
+            );
+        }
+
+        #[ink::test]
+        fn player_that_cranks_the_game_is_not_punished() {
+            let charlie = default_accounts::<DefaultEnvironment>().charlie;
+            let mut game = running_game(0, |game| {
+                game.set_turn_mode(TurnMode::Calls).unwrap();
+                game.set_strike_limit(Some(1), false).unwrap();
+            });
+            advance_block::<DefaultEnvironment>();
+            set_caller::<DefaultEnvironment>(charlie);
+
+            game.submit_turn().unwrap();
+
+            let player = &game.players_sorted()[0];
+            assert_eq!(player.status, PlayerStatus::Active);
+            assert_eq!(player.strikes, 0);
+            assert_eq!(player.stats.broken, 0);
+            assert!(game.last_error(charlie).is_none());
+        }
+
+        #[ink::test]
+        fn only_opener_can_reset_the_game() {
+            let mut game = running_game(0, |_| ());
+            play_rounds(&mut game, 4);
+            game.end_game().unwrap();
+
+            let bob = default_accounts::<DefaultEnvironment>().bob;
+            set_caller::<DefaultEnvironment>(bob);
+            assert_eq!(game.reset_game(), Err(GameError::OnlyAdminCanResetTheGame));
+        }
+
+        #[ink::test]
+        fn prize_is_owed_to_the_owner_until_claimed() {
+            let accounts = default_accounts::<DefaultEnvironment>();
+            let mut game = running_game(100, |_| ());
+            play_rounds(&mut game, 4);
+            game.end_game().unwrap();
+            assert_eq!(game.unclaimed_prize(accounts.bob), 100);
+            assert_eq!(game.reserved(), 100);
+
+            set_caller::<DefaultEnvironment>(accounts.django);
+            game.claim_prize(accounts.bob).unwrap();
+
+            assert_eq!(game.unclaimed_prize(accounts.bob), 0);
+            assert_eq!(game.reserved(), 0);
+            assert_eq!(
+                game.claim_prize(accounts.bob),
+                Err(GameError::NoPrizeToClaim)
+            );
+        }
+
+        #[ink::test]
+        fn rake_is_owed_to_the_treasury() {
+            let accounts = default_accounts::<DefaultEnvironment>();
+            let mut game = running_game(100, |game| {
+                game.set_rake(1_000, accounts.django).unwrap()
+            });
+            play_rounds(&mut game, 4);
+            game.end_game().unwrap();
+
+            assert_eq!(game.unclaimed_prize(accounts.django), 10);
+            assert_eq!(game.unclaimed_prize(accounts.bob), 90);
+            assert_eq!(game.reserved(), 100);
+        }
+
+        #[ink::test]
+        fn rollover_is_added_to_the_next_pot() {
+            let accounts = default_accounts::<DefaultEnvironment>();
+            let mut game = running_game(100, |game| game.set_rollover(5_000).unwrap());
+            play_rounds(&mut game, 4);
+            game.end_game().unwrap();
+            assert_eq!(game.unclaimed_prize(accounts.bob), 50);
+            assert_eq!(game.reserved(), 100);
+
+            game.reset_game().unwrap();
+
+            assert_eq!(game.pot(), 50);
+            assert_eq!(game.reserved(), 100);
+        }
+
+        #[ink::test]
+        fn winner_destroys_the_game_once_everything_is_paid_out() {
+            let accounts = default_accounts::<DefaultEnvironment>();
+            let mut game = running_game(100, |game| {
+                game.set_rake(0, accounts.django).unwrap();
+                game.set_rollover(5_000).unwrap();
+            });
+            play_rounds(&mut game, 4);
+            game.end_game().unwrap();
+
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(game.destroy(), Err(GameError::AllPrizesNeedToBeClaimedFirst));
+            game.claim_prize(accounts.bob).unwrap();
+            assert_eq!(game.destroy(), Err(GameError::AllPrizesNeedToBeClaimedFirst));
+
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            game.release_reserves().unwrap();
+            assert_eq!(game.unclaimed_prize(accounts.django), 50);
+            game.claim_prize(accounts.django).unwrap();
+
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let balance = ink::env::balance::<DefaultEnvironment>();
+            ink::env::test::assert_contract_termination::<DefaultEnvironment, _>(
+                move || game.destroy().unwrap(),
+                accounts.charlie,
+                balance,
+            );
+        }
+
+        #[ink::test]
+        fn keeper_is_paid_for_a_limited_number_of_rounds() {
+            let mut game = running_game(100, |game| {
+                game.set_keeper(Some(KeeperConfig {
+                    fee: 10,
+                    pot_share_bps: 5_000,
+                    max_rounds_per_keeper: 2,
+                }))
+                .unwrap()
+            });
+            assert_eq!(game.keeper_fund(), 50);
+            assert_eq!(game.pot(), 50);
+
+            play_rounds(&mut game, 3);
+
+            assert_eq!(game.keeper_fund(), 30);
+            assert_eq!(game.reserved(), 80);
+        }
+
+        #[ink::test]
+        fn keeper_fund_is_released_to_the_treasury() {
+            let accounts = default_accounts::<DefaultEnvironment>();
+            let mut game = running_game(100, |game| {
+                game.set_rake(0, accounts.django).unwrap();
+                game.set_keeper(Some(KeeperConfig {
+                    fee: 0,
+                    pot_share_bps: 5_000,
+                    max_rounds_per_keeper: 0,
+                }))
+                .unwrap();
+            });
+            set_value_transferred::<DefaultEnvironment>(20);
+            game.fund_keepers(0).unwrap();
+            set_value_transferred::<DefaultEnvironment>(0);
+            play_rounds(&mut game, 4);
+            game.end_game().unwrap();
+
+            set_value_transferred::<DefaultEnvironment>(20);
+            assert_eq!(
+                game.fund_keepers(0),
+                Err(GameError::KeeperFundCanOnlyBeToppedUpBeforeTheGameEnded)
+            );
+            set_value_transferred::<DefaultEnvironment>(0);
+            game.release_reserves().unwrap();
+
+            assert_eq!(game.keeper_fund(), 0);
+            assert_eq!(game.unclaimed_prize(accounts.django), 70);
+            assert_eq!(game.reserved(), 120);
+        }
+
+        /// Play `rounds` rounds in which nobody makes a move.
+        fn play_rounds(game: &mut SquinkSplash, rounds: u32) {
+            let alice = default_accounts::<DefaultEnvironment>().alice;
+            set_caller::<DefaultEnvironment>(alice);
+            for _ in 0..rounds {
+                advance_block::<DefaultEnvironment>();
+                game.submit_signed_turns(Vec::new()).unwrap();
+            }
+        }
+
+        /// A running game of four rounds with a `buy_in` in which Bob plays the
+        /// player contract Charlie.
+        ///
+        /// Alice is the opener and the relayer and configures the game with
+        /// `configure` first.
+        fn running_game(
+            buy_in: Balance,
+            configure: impl FnOnce(&mut SquinkSplash),
+        ) -> SquinkSplash {
+            let accounts = default_accounts::<DefaultEnvironment>();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut game = SquinkSplash::new(Field { x: 4, y: 4 }, buy_in, 0, 4);
+            game.set_turn_mode(TurnMode::SignedMoves).unwrap();
+            configure(&mut game);
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_value_transferred::<DefaultEnvironment>(buy_in);
+            game.register_player(accounts.charlie, String::from("charlie"))
+                .unwrap();
+            set_value_transferred::<DefaultEnvironment>(0);
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            game.start_game().unwrap();
+            game
+        }
+
+        /// A key pair and the account ID derived from it.
+        fn signer(seed: u8) -> (SecretKey, AccountId) {
+            let key = SecretKey::from_slice(&[seed; 32]).unwrap();
+            let public_key = PublicKey::from_secret_key(SECP256K1, &key).serialize();
+            let mut account = [0; 32];
+            ink::env::hash_bytes::<Blake2x256>(&public_key, &mut account);
+            (key, AccountId::from(account))
+        }
+
+        /// Sign `turn` for `player` in the first round of the game number
+        /// `games_started`.
+        fn signed_move(
+            key: &SecretKey,
+            player: AccountId,
+            games_started: u32,
+            turn: Field,
+        ) -> SignedMove {
+            let hash = SquinkSplash::signed_move_hash(
+                ink::env::account_id::<DefaultEnvironment>(),
+                games_started,
+                0,
+                turn,
+            );
+            let (recovery_id, compact) = SECP256K1
+                .sign_ecdsa_recoverable(&Message::from_slice(&hash).unwrap(), key)
+                .serialize_compact();
+            let mut signature = [0; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = recovery_id.to_i32() as u8;
+            SignedMove {
+                player,
+                turn,
+                signature,
+            }
+        }
+
+        /// A running game in which `owner` plays a single human player.
+        ///
+        /// Alice is the opener and the relayer and configures the game with
+        /// `configure` first.
+        fn game_with_signed_moves(
+            owner: AccountId,
+            configure: impl FnOnce(&mut SquinkSplash),
+        ) -> SquinkSplash {
+            let accounts = default_accounts::<DefaultEnvironment>();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut game = SquinkSplash::new(Field { x: 4, y: 4 }, 0, 0, 8);
+            game.set_turn_mode(TurnMode::SignedMoves).unwrap();
+            configure(&mut game);
+            set_caller::<DefaultEnvironment>(owner);
+            game.register_human(String::from("owner")).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            game.start_game().unwrap();
+            advance_block::<DefaultEnvironment>();
+            game
         }
     }
 }
@@ -0,0 +1,290 @@
+//! Ratings of the owners of players across many games.
+//!
+//! Finished games report their final standings to this contract. Only games whose
+//! code hash was allowed by the admin can do that.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![allow(clippy::new_without_default)]
+
+pub use contract::{
+    Ratings,
+    RatingsRef,
+};
+
+/// The rating every owner starts with.
+pub const INITIAL_RATING: u32 = 1500;
+
+/// The number of owners kept on the leaderboard.
+pub const LEADERBOARD_SIZE: usize = 100;
+
+/// The maximum number of rating points a single pairing can move.
+const K_FACTOR: i64 = 32;
+
+/// The expected score in permille of a player with `rating` against `opponent`.
+///
+/// This is a linear approximation of the logistic curve used by Elo because
+/// contracts can't use floats.
+fn expected_permille(rating: u32, opponent: u32) -> i64 {
+    let diff = i64::from(rating) - i64::from(opponent);
+    (500 + diff * 1000 / 800).clamp(50, 950)
+}
+
+/// The new ratings after a game for `ratings` ordered from first to last place.
+///
+/// Every player is compared with every other player. A player won against everyone
+/// ranked below it. The changes are averaged over all opponents.
+pub fn updated_ratings(ratings: &[u32]) -> ink::prelude::vec::Vec<u32> {
+    let num_players = ratings.len();
+    if num_players < 2 {
+        return ratings.to_vec()
+    }
+    let mut changes = ink::prelude::vec![0i64; num_players];
+    for winner in 0..num_players {
+        for loser in winner + 1..num_players {
+            let change =
+                K_FACTOR * (1000 - expected_permille(ratings[winner], ratings[loser]));
+            changes[winner] += change;
+            changes[loser] -= change;
+        }
+    }
+    let scale = 1000 * (num_players as i64 - 1);
+    ratings
+        .iter()
+        .zip(changes)
+        .map(|(rating, change)| {
+            (i64::from(*rating) + change / scale).clamp(0, i64::from(u32::MAX)) as u32
+        })
+        .collect()
+}
+
+#[ink::contract]
+mod contract {
+    use common::RatingsError;
+    use ink::{
+        prelude::vec::Vec,
+        storage::{
+            Lazy,
+            Mapping,
+        },
+    };
+
+    /// The rating of a single owner.
+    #[derive(scale::Decode, scale::Encode, Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Rating {
+        /// The current rating.
+        pub rating: u32,
+        /// The number of games that were reported for the owner.
+        pub games: u32,
+        /// The best rank the owner reached in any game starting at 1.
+        pub best_rank: u32,
+    }
+
+    #[ink(storage)]
+    pub struct Ratings {
+        /// The account that decides which games can report results.
+        admin: AccountId,
+        /// Code hashes of the games that can report results.
+        allowed_code_hashes: Mapping<Hash, ()>,
+        /// The rating of every owner that took part in a reported game.
+        ratings: Mapping<AccountId, Rating>,
+        /// All owners that have a rating by the order they were first rated.
+        owners: Mapping<u32, AccountId>,
+        /// The number of owners that have a rating.
+        num_owners: u32,
+        /// The best rated owners sorted by rating.
+        ///
+        /// Bounded by [`crate::LEADERBOARD_SIZE`]. Only owners of reported games are
+        /// compared with it, so an owner that drops out only comes back after
+        /// playing again.
+        leaderboard: Lazy<Vec<AccountId>>,
+    }
+
+    /// A game reported its final standings.
+    #[ink(event)]
+    pub struct ResultReported {
+        /// The game that reported.
+        #[ink(topic)]
+        game: AccountId,
+        /// The number of owners that were rated.
+        owners: u32,
+    }
+
+    impl Ratings {
+        /// Create a new ratings contract with the caller as admin.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let mut ret = Self {
+                admin: Self::env().caller(),
+                allowed_code_hashes: Default::default(),
+                ratings: Default::default(),
+                owners: Default::default(),
+                num_owners: 0,
+                leaderboard: Default::default(),
+            };
+            ret.leaderboard.set(&Vec::new());
+            ret
+        }
+
+        /// Allow or disallow games with `code_hash` to report results.
+        ///
+        /// Only the admin can do this.
+        #[ink(message)]
+        pub fn set_code_hash_allowed(
+            &mut self,
+            code_hash: Hash,
+            allowed: bool,
+        ) -> Result<(), RatingsError> {
+            (self.env().caller() == self.admin)
+                .then_some(())
+                .ok_or(RatingsError::OnlyAdminCanConfigure)?;
+            if allowed {
+                self.allowed_code_hashes.insert(code_hash, &());
+            } else {
+                self.allowed_code_hashes.remove(code_hash);
+            }
+            Ok(())
+        }
+
+        /// Whether games with `code_hash` can report results.
+        #[ink(message)]
+        pub fn is_code_hash_allowed(&self, code_hash: Hash) -> bool {
+            self.allowed_code_hashes.contains(code_hash)
+        }
+
+        /// Rate the owners of the players of the calling game.
+        ///
+        /// `owners` are ordered from first to last place. Owners with more than one
+        /// player are only rated by their best player.
+        #[ink(message)]
+        pub fn report(&mut self, owners: Vec<AccountId>) -> Result<(), RatingsError> {
+            let game = self.env().caller();
+            self.env()
+                .code_hash(&game)
+                .ok()
+                .filter(|code_hash| self.allowed_code_hashes.contains(code_hash))
+                .ok_or(RatingsError::GameCodeHashIsNotAllowed)?;
+
+            let mut ranked: Vec<AccountId> = Vec::new();
+            for owner in owners {
+                if !ranked.contains(&owner) {
+                    ranked.push(owner);
+                }
+            }
+
+            let previous: Vec<Rating> = ranked
+                .iter()
+                .map(|owner| {
+                    self.ratings.get(owner).unwrap_or_else(|| {
+                        self.owners.insert(self.num_owners, owner);
+                        self.num_owners = self.num_owners.saturating_add(1);
+                        Rating {
+                            rating: crate::INITIAL_RATING,
+                            games: 0,
+                            best_rank: u32::MAX,
+                        }
+                    })
+                })
+                .collect();
+            let updated = crate::updated_ratings(
+                &previous.iter().map(|rating| rating.rating).collect::<Vec<_>>(),
+            );
+
+            for (rank, ((owner, previous), rating)) in
+                (1..).zip(ranked.iter().zip(previous).zip(updated))
+            {
+                self.ratings.insert(
+                    owner,
+                    &Rating {
+                        rating,
+                        games: previous.games.saturating_add(1),
+                        best_rank: previous.best_rank.min(rank),
+                    },
+                );
+            }
+            self.update_leaderboard(&ranked);
+            self.env().emit_event(ResultReported {
+                game,
+                owners: ranked.len() as u32,
+            });
+            Ok(())
+        }
+
+        /// The rating of `owner` if it took part in a reported game.
+        #[ink(message)]
+        pub fn rating(&self, owner: AccountId) -> Option<Rating> {
+            self.ratings.get(owner)
+        }
+
+        /// The number of owners that have a rating.
+        #[ink(message)]
+        pub fn num_rated(&self) -> u32 {
+            self.num_owners
+        }
+
+        /// Up to `limit` rated owners starting at index `offset` by the order they
+        /// were first rated.
+        #[ink(message)]
+        pub fn rated(&self, offset: u32, limit: u32) -> Vec<(AccountId, Rating)> {
+            (offset..offset.saturating_add(limit).min(self.num_owners))
+                .filter_map(|index| {
+                    let owner = self.owners.get(index)?;
+                    Some((owner, self.ratings.get(owner)?))
+                })
+                .collect()
+        }
+
+        /// Up to `limit` owners of the leaderboard starting at position `offset`.
+        #[ink(message)]
+        pub fn leaderboard(&self, offset: u32, limit: u32) -> Vec<(AccountId, Rating)> {
+            self.leaderboard
+                .get()
+                .unwrap_or_default()
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .filter_map(|owner| Some((owner, self.ratings.get(owner)?)))
+                .collect()
+        }
+
+        /// Move the freshly rated `owners` to their place on the leaderboard.
+        fn update_leaderboard(&mut self, owners: &[AccountId]) {
+            let mut leaderboard = self.leaderboard.get().unwrap_or_default();
+            leaderboard.retain(|owner| !owners.contains(owner));
+            let rating_of =
+                |owner: &AccountId| self.ratings.get(owner).map_or(0, |r| r.rating);
+            for owner in owners {
+                let rating = rating_of(owner);
+                let position =
+                    leaderboard.partition_point(|other| rating_of(other) >= rating);
+                if position < crate::LEADERBOARD_SIZE {
+                    leaderboard.insert(position, *owner);
+                }
+            }
+            leaderboard.truncate(crate::LEADERBOARD_SIZE);
+            self.leaderboard.set(&leaderboard);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn winner_of_even_game_gains_what_loser_loses() {
+        assert_eq!(updated_ratings(&[1500, 1500]), [1516, 1484]);
+    }
+
+    #[test]
+    fn upset_moves_ratings_more() {
+        let [weak, strong] = updated_ratings(&[1300, 1700])[..] else {
+            panic!("two ratings expected");
+        };
+        assert!(weak - 1300 > 16);
+        assert!(1700 - strong > 16);
+    }
+}
@@ -0,0 +1,137 @@
+//! A registry of display names and metadata shared by many games.
+//!
+//! Every owner can reserve one name that nobody else can use. Games configured with
+//! the registry fetch the name of an owner from here and reject names reserved by
+//! someone else.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![allow(clippy::new_without_default)]
+
+pub use contract::{
+    Registry,
+    RegistryRef,
+};
+
+#[ink::contract]
+mod contract {
+    use common::{
+        Identity,
+        ALLOWED_NAME_SIZES,
+        ALLOWED_TEAM_SIZES,
+    };
+    use ink::{
+        prelude::string::String,
+        storage::Mapping,
+    };
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum RegistryError {
+        InvalidLengthForName,
+        InvalidLengthForTeam,
+        ThisNameIsAlreadyTaken,
+        NoIdentityReserved,
+    }
+
+    #[ink(storage)]
+    pub struct Registry {
+        /// The identity reserved by every owner.
+        identities: Mapping<AccountId, Identity>,
+        /// The owner of every reserved name.
+        names: Mapping<String, AccountId>,
+    }
+
+    /// An owner reserved a name or changed its identity.
+    #[ink(event)]
+    pub struct IdentityReserved {
+        /// The owner of the identity.
+        #[ink(topic)]
+        owner: AccountId,
+        /// The reserved name.
+        name: String,
+    }
+
+    /// An owner gave up its identity.
+    #[ink(event)]
+    pub struct IdentityReleased {
+        /// The former owner of the identity.
+        #[ink(topic)]
+        owner: AccountId,
+        /// The name that is free again.
+        name: String,
+    }
+
+    impl Registry {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                identities: Default::default(),
+                names: Default::default(),
+            }
+        }
+
+        /// Reserve `identity` for the caller.
+        ///
+        /// Replaces the previous identity of the caller and frees its name.
+        #[ink(message)]
+        pub fn reserve(&mut self, identity: Identity) -> Result<(), RegistryError> {
+            ALLOWED_NAME_SIZES
+                .contains(&identity.name.len())
+                .then_some(())
+                .ok_or(RegistryError::InvalidLengthForName)?;
+            identity
+                .team
+                .as_ref()
+                .map_or(true, |team| ALLOWED_TEAM_SIZES.contains(&team.len()))
+                .then_some(())
+                .ok_or(RegistryError::InvalidLengthForTeam)?;
+
+            let owner = self.env().caller();
+            self.names
+                .get(&identity.name)
+                .map_or(true, |holder| holder == owner)
+                .then_some(())
+                .ok_or(RegistryError::ThisNameIsAlreadyTaken)?;
+
+            if let Some(previous) = self.identities.get(owner) {
+                self.names.remove(previous.name);
+            }
+            self.names.insert(&identity.name, &owner);
+            self.identities.insert(owner, &identity);
+            self.env().emit_event(IdentityReserved {
+                owner,
+                name: identity.name,
+            });
+            Ok(())
+        }
+
+        /// Give up the identity of the caller so that its name can be reserved by
+        /// someone else.
+        #[ink(message)]
+        pub fn release(&mut self) -> Result<(), RegistryError> {
+            let owner = self.env().caller();
+            let identity = self
+                .identities
+                .take(owner)
+                .ok_or(RegistryError::NoIdentityReserved)?;
+            self.names.remove(&identity.name);
+            self.env().emit_event(IdentityReleased {
+                owner,
+                name: identity.name,
+            });
+            Ok(())
+        }
+
+        /// The identity reserved by `owner`.
+        #[ink(message)]
+        pub fn identity(&self, owner: AccountId) -> Option<Identity> {
+            self.identities.get(owner)
+        }
+
+        /// The owner that reserved `name`.
+        #[ink(message)]
+        pub fn owner_of(&self, name: String) -> Option<AccountId> {
+            self.names.get(name)
+        }
+    }
+}
@@ -0,0 +1,351 @@
+//! A PSP34 collection of trophies for the top players of finished games.
+//!
+//! Games mint trophies through [`Trophies::mint`] when they end. Only games whose
+//! code hash was allowed by the admin can do that. The metadata of every trophy is
+//! available through [`PSP34Metadata::get_attribute`].
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![allow(clippy::new_without_default)]
+
+use common::{
+    PSP34Error,
+    Trophy,
+};
+use ink::{
+    prelude::vec::Vec,
+    primitives::AccountId,
+};
+
+pub use contract::{
+    TrophyCollection,
+    TrophyCollectionRef,
+};
+
+/// The identifier of a token of the PSP34 standard.
+#[derive(scale::Decode, scale::Encode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Id {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Bytes(Vec<u8>),
+}
+
+/// The messages of the PSP34 standard.
+#[ink::trait_definition]
+pub trait PSP34 {
+    #[ink(message)]
+    fn collection_id(&self) -> Id;
+
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> u32;
+
+    #[ink(message)]
+    fn owner_of(&self, id: Id) -> Option<AccountId>;
+
+    #[ink(message)]
+    fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<Id>) -> bool;
+
+    #[ink(message)]
+    fn approve(
+        &mut self,
+        operator: AccountId,
+        id: Option<Id>,
+        approved: bool,
+    ) -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, id: Id, data: Vec<u8>)
+        -> Result<(), PSP34Error>;
+
+    #[ink(message)]
+    fn total_supply(&self) -> u128;
+}
+
+/// The metadata extension of the PSP34 standard.
+#[ink::trait_definition]
+pub trait PSP34Metadata {
+    /// The SCALE encoded value of the attribute `key` of the token `id`.
+    #[ink(message)]
+    fn get_attribute(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>>;
+}
+
+/// The message games call to mint trophies.
+#[ink::trait_definition]
+pub trait Trophies {
+    /// Mint a new token for `trophy` to `to`.
+    #[ink(message)]
+    fn mint(&mut self, to: AccountId, trophy: Trophy) -> Result<(), PSP34Error>;
+}
+
+#[ink::contract]
+mod contract {
+    use crate::{
+        Id,
+        Trophies,
+        PSP34Metadata,
+        PSP34,
+    };
+    use common::{
+        PSP34Error,
+        Trophy,
+    };
+    use ink::{
+        prelude::{
+            string::String,
+            vec::Vec,
+        },
+        storage::Mapping,
+    };
+
+    #[ink(storage)]
+    pub struct TrophyCollection {
+        /// The account that decides which games can mint trophies.
+        admin: AccountId,
+        /// Code hashes of the games that can mint trophies.
+        allowed_code_hashes: Mapping<Hash, ()>,
+        /// The number of trophies minted so far. Also the ID of the next trophy.
+        total_supply: u128,
+        /// The metadata of every trophy.
+        trophies: Mapping<u128, Trophy>,
+        /// The owner of every trophy.
+        owners: Mapping<u128, AccountId>,
+        /// The number of trophies every account owns.
+        balances: Mapping<AccountId, u32>,
+        /// The account that is allowed to transfer a single trophy.
+        token_approvals: Mapping<u128, AccountId>,
+        /// Operators that are allowed to transfer all trophies of an owner.
+        operator_approvals: Mapping<(AccountId, AccountId), ()>,
+    }
+
+    /// A trophy was minted or transferred.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        id: Id,
+    }
+
+    /// An operator was allowed or disallowed to transfer trophies of an owner.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        id: Option<Id>,
+        approved: bool,
+    }
+
+    impl TrophyCollection {
+        /// Create a new collection with the caller as admin.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                admin: Self::env().caller(),
+                allowed_code_hashes: Default::default(),
+                total_supply: 0,
+                trophies: Default::default(),
+                owners: Default::default(),
+                balances: Default::default(),
+                token_approvals: Default::default(),
+                operator_approvals: Default::default(),
+            }
+        }
+
+        /// Allow or disallow games with `code_hash` to mint trophies.
+        ///
+        /// Only the admin can do this.
+        #[ink(message)]
+        pub fn set_code_hash_allowed(
+            &mut self,
+            code_hash: Hash,
+            allowed: bool,
+        ) -> Result<(), PSP34Error> {
+            (self.env().caller() == self.admin)
+                .then_some(())
+                .ok_or(PSP34Error::Custom(String::from("OnlyAdminCanConfigure")))?;
+            if allowed {
+                self.allowed_code_hashes.insert(code_hash, &());
+            } else {
+                self.allowed_code_hashes.remove(code_hash);
+            }
+            Ok(())
+        }
+
+        /// Whether games with `code_hash` can mint trophies.
+        #[ink(message)]
+        pub fn is_code_hash_allowed(&self, code_hash: Hash) -> bool {
+            self.allowed_code_hashes.contains(code_hash)
+        }
+
+        /// The metadata of the trophy `id`.
+        #[ink(message)]
+        pub fn trophy(&self, id: Id) -> Option<Trophy> {
+            self.trophies.get(Self::token(&id)?)
+        }
+
+        /// The trophies only use `U128` IDs.
+        fn token(id: &Id) -> Option<u128> {
+            match id {
+                Id::U128(token) => Some(*token),
+                _ => None,
+            }
+        }
+
+        fn move_token(&mut self, from: Option<AccountId>, to: AccountId, token: u128) {
+            if let Some(from) = from {
+                let balance = self.balances.get(from).unwrap_or_default();
+                self.balances.insert(from, &balance.saturating_sub(1));
+            }
+            let balance = self.balances.get(to).unwrap_or_default();
+            self.balances.insert(to, &balance.saturating_add(1));
+            self.owners.insert(token, &to);
+            self.token_approvals.remove(token);
+            self.env().emit_event(Transfer {
+                from,
+                to: Some(to),
+                id: Id::U128(token),
+            });
+        }
+    }
+
+    impl PSP34 for TrophyCollection {
+        #[ink(message)]
+        fn collection_id(&self) -> Id {
+            Id::Bytes(<_ as AsRef<[u8]>>::as_ref(&self.env().account_id()).to_vec())
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u32 {
+            self.balances.get(owner).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        fn owner_of(&self, id: Id) -> Option<AccountId> {
+            self.owners.get(Self::token(&id)?)
+        }
+
+        #[ink(message)]
+        fn allowance(
+            &self,
+            owner: AccountId,
+            operator: AccountId,
+            id: Option<Id>,
+        ) -> bool {
+            if self.operator_approvals.contains((owner, operator)) {
+                return true
+            }
+            let Some(token) = id.as_ref().and_then(Self::token) else {
+                return false
+            };
+            self.owners.get(token) == Some(owner)
+                && self.token_approvals.get(token) == Some(operator)
+        }
+
+        #[ink(message)]
+        fn approve(
+            &mut self,
+            operator: AccountId,
+            id: Option<Id>,
+            approved: bool,
+        ) -> Result<(), PSP34Error> {
+            let owner = self.env().caller();
+            (owner != operator)
+                .then_some(())
+                .ok_or(PSP34Error::SelfApprove)?;
+            match id.as_ref() {
+                Some(id) => {
+                    let token = Self::token(id).ok_or(PSP34Error::TokenNotExists)?;
+                    let token_owner =
+                        self.owners.get(token).ok_or(PSP34Error::TokenNotExists)?;
+                    (token_owner == owner)
+                        .then_some(())
+                        .ok_or(PSP34Error::NotApproved)?;
+                    if approved {
+                        self.token_approvals.insert(token, &operator);
+                    } else {
+                        self.token_approvals.remove(token);
+                    }
+                }
+                None if approved => {
+                    self.operator_approvals.insert((owner, operator), &());
+                }
+                None => {
+                    self.operator_approvals.remove((owner, operator));
+                }
+            }
+            self.env().emit_event(Approval {
+                owner,
+                operator,
+                id,
+                approved,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            id: Id,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP34Error> {
+            let token = Self::token(&id).ok_or(PSP34Error::TokenNotExists)?;
+            let owner = self.owners.get(token).ok_or(PSP34Error::TokenNotExists)?;
+            let caller = self.env().caller();
+            (caller == owner || self.allowance(owner, caller, Some(id)))
+                .then_some(())
+                .ok_or(PSP34Error::NotApproved)?;
+            self.move_token(Some(owner), to, token);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.total_supply
+        }
+    }
+
+    impl PSP34Metadata for TrophyCollection {
+        /// The keys are `game`, `rank`, `score`, `gas_used` and `board_hash`.
+        #[ink(message)]
+        fn get_attribute(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>> {
+            let trophy = self.trophies.get(Self::token(&id)?)?;
+            let value = match &key[..] {
+                b"game" => scale::Encode::encode(&trophy.game),
+                b"rank" => scale::Encode::encode(&trophy.rank),
+                b"score" => scale::Encode::encode(&trophy.score),
+                b"gas_used" => scale::Encode::encode(&trophy.gas_used),
+                b"board_hash" => scale::Encode::encode(&trophy.board_hash),
+                _ => return None,
+            };
+            Some(value)
+        }
+    }
+
+    impl Trophies for TrophyCollection {
+        /// Only games with an allowed code hash can mint and only trophies of
+        /// themselves.
+        #[ink(message)]
+        fn mint(&mut self, to: AccountId, trophy: Trophy) -> Result<(), PSP34Error> {
+            let game = self.env().caller();
+            self.env()
+                .code_hash(&game)
+                .ok()
+                .filter(|code_hash| self.allowed_code_hashes.contains(code_hash))
+                .filter(|_| trophy.game == game)
+                .ok_or(PSP34Error::Custom(String::from("GameIsNotAllowedToMint")))?;
+
+            let token = self.total_supply;
+            self.total_supply = token.saturating_add(1);
+            self.trophies.insert(token, &trophy);
+            self.move_token(None, to, token);
+            Ok(())
+        }
+    }
+}